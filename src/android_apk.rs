@@ -0,0 +1,381 @@
+//! Orchestrates the packaging of compiled classes and resources into an APK.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::env_paths;
+use crate::{JarBuild, JarMode, JavaRun};
+
+/// A builder that packages compiled Java classes and Android resources
+/// into an aligned and (optionally) signed APK file.
+///
+/// Invoking [`AndroidApk::build()`] runs the following steps in order:
+/// 1. `aapt2 compile`: compiles the resources directory, if one was given.
+/// 2. `aapt2 link`: links the manifest and compiled resources into an unaligned APK.
+/// 3. `d8`: compiles all class files in the classes directory into DEX files.
+/// 4. `jar`: adds the DEX files to the unaligned APK.
+/// 5. `zipalign`: aligns the APK.
+/// 6. `apksigner`: signs the aligned APK, unless signing is skipped.
+///
+/// Intermediate files are placed in the configured working directory.
+/// The commands for each step can be inspected using [`AndroidApk::steps()`].
+#[derive(Clone, Debug, Default)]
+pub struct AndroidApk {
+    /// The version of the Android build tools to use.
+    build_tools_version: Option<String>,
+    /// Override the default `android.jar` path.
+    android_jar: Option<PathBuf>,
+    /// The `AndroidManifest.xml` file.
+    manifest: Option<PathBuf>,
+    /// The directory of Android resources (`res/`) to be compiled.
+    res_dir: Option<PathBuf>,
+    /// The directory containing the compiled class files.
+    classes_dir: Option<PathBuf>,
+    /// The directory in which intermediate files are placed.
+    work_dir: Option<PathBuf>,
+    /// The path of the final APK file.
+    output_apk: Option<PathBuf>,
+    /// The minimum Android API level supported by the APK.
+    min_sdk: Option<u32>,
+    /// The Android API level targeted by the APK.
+    target_sdk: Option<u32>,
+    /// The keystore used to sign the APK.
+    keystore: Option<PathBuf>,
+    /// The password of the keystore, in `apksigner`'s format, e.g., `pass:android`.
+    keystore_password: Option<String>,
+    /// The alias of the key within the keystore to sign with.
+    key_alias: Option<String>,
+    /// If `true`, the APK is aligned but not signed.
+    skip_signing: bool,
+}
+
+impl AndroidApk {
+    /// Creates a new `AndroidApk` instance with default values,
+    /// which can be further customized using the builder methods.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Runs all packaging steps in order, stopping at the first step that fails.
+    ///
+    /// Any error includes the name of the step that failed.
+    pub fn build(&self) -> std::io::Result<()> {
+        let work_dir = self.effective_work_dir()?;
+        std::fs::create_dir_all(work_dir.join("dex"))?;
+
+        for (step, mut cmd) in self.steps()? {
+            let status = cmd.status().map_err(|e| std::io::Error::new(
+                e.kind(),
+                format!("APK packaging step `{}` could not be run: {}", step, e),
+            ))?;
+            crate::process::check_status(step, status, None)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the name and [`Command`] for each packaging step, in the order
+    /// in which they are run by [`AndroidApk::build()`].
+    ///
+    /// The commands can be inspected or customized before being executed.
+    /// Note that the `d8` step requires the class files to already exist,
+    /// as they are enumerated here.
+    pub fn steps(&self) -> std::io::Result<Vec<(&'static str, Command)>> {
+        let missing = |what: &str| std::io::Error::other(format!("No {} was specified.", what));
+        let bt = self.build_tools_version.as_deref();
+        let work_dir = self.effective_work_dir()?;
+        let manifest = self.manifest.as_ref().ok_or_else(|| missing("manifest"))?;
+        let classes_dir = self.classes_dir.as_ref().ok_or_else(|| missing("classes directory"))?;
+        let output_apk = self.output_apk.as_ref().ok_or_else(|| missing("output APK path"))?;
+        let android_jar = self.android_jar.clone()
+            .or_else(|| env_paths::android_jar(self.target_sdk.map(|l| format!("android-{}", l)).as_deref()))
+            .ok_or_else(|| std::io::Error::other("Could not find android.jar."))?;
+        let aapt2 = env_paths::aapt2(bt)
+            .ok_or_else(|| std::io::Error::other("Could not find aapt2."))?;
+        let d8_jar = env_paths::android_d8_jar(bt)
+            .ok_or_else(|| std::io::Error::other("Could not find d8.jar."))?;
+        let zipalign = env_paths::zipalign(bt)
+            .ok_or_else(|| std::io::Error::other("Could not find zipalign."))?;
+
+        let compiled_res = work_dir.join("resources.zip");
+        let unaligned_apk = work_dir.join("unaligned.apk");
+        let aligned_apk = if self.skip_signing {
+            output_apk.clone()
+        } else {
+            work_dir.join("aligned.apk")
+        };
+        let dex_dir = work_dir.join("dex");
+
+        let mut steps = Vec::new();
+
+        if let Some(res_dir) = self.res_dir.as_ref() {
+            let mut cmd = Command::new(&aapt2);
+            cmd.arg("compile").arg("--dir").arg(res_dir).arg("-o").arg(&compiled_res);
+            steps.push(("aapt2 compile", cmd));
+        }
+
+        let mut cmd = Command::new(&aapt2);
+        cmd.arg("link")
+            .arg("-I").arg(&android_jar)
+            .arg("--manifest").arg(manifest)
+            .arg("-o").arg(&unaligned_apk);
+        if let Some(min_sdk) = self.min_sdk {
+            cmd.arg("--min-sdk-version").arg(min_sdk.to_string());
+        }
+        if let Some(target_sdk) = self.target_sdk {
+            cmd.arg("--target-sdk-version").arg(target_sdk.to_string());
+        }
+        if self.res_dir.is_some() {
+            cmd.arg(&compiled_res);
+        }
+        steps.push(("aapt2 link", cmd));
+
+        let class_files = crate::files::collect_files_by_ext(classes_dir, "class")?;
+        let mut d8 = JavaRun::new();
+        d8.class_path(&d8_jar)
+            .main_class("com.android.tools.r8.D8")
+            .arg("--lib").arg(&android_jar)
+            .arg("--output").arg(&dex_dir);
+        if let Some(min_sdk) = self.min_sdk {
+            d8.arg("--min-api").arg(min_sdk.to_string());
+        }
+        d8.args(class_files);
+        steps.push(("d8", d8.command()?));
+
+        steps.push(("jar", JarBuild::new()
+            .mode(JarMode::Update)
+            .jar_file(&unaligned_apk)
+            .base_dir(&dex_dir)
+            .command()?
+        ));
+
+        let mut cmd = Command::new(&zipalign);
+        cmd.arg("-f").arg("-p").arg("4").arg(&unaligned_apk).arg(&aligned_apk);
+        steps.push(("zipalign", cmd));
+
+        if !self.skip_signing {
+            let apksigner = env_paths::apksigner(bt)
+                .ok_or_else(|| std::io::Error::other("Could not find apksigner."))?;
+            let keystore = self.keystore.as_ref().ok_or_else(|| missing("keystore"))?;
+            let mut cmd = Command::new(apksigner);
+            cmd.arg("sign").arg("--ks").arg(keystore);
+            if let Some(password) = self.keystore_password.as_ref() {
+                cmd.arg("--ks-pass").arg(password);
+            }
+            if let Some(alias) = self.key_alias.as_ref() {
+                cmd.arg("--ks-key-alias").arg(alias);
+            }
+            cmd.arg("--out").arg(output_apk).arg(&aligned_apk);
+            steps.push(("apksigner", cmd));
+        }
+
+        Ok(steps)
+    }
+
+    /// Returns the working directory for intermediate files.
+    ///
+    /// If not set, this defaults to the directory containing the output APK.
+    fn effective_work_dir(&self) -> std::io::Result<PathBuf> {
+        self.work_dir.clone()
+            .or_else(|| self.output_apk.as_deref()
+                .and_then(Path::parent)
+                .map(Path::to_path_buf)
+            )
+            .ok_or_else(|| std::io::Error::other("No working directory or output APK path was specified."))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    //////////////////////// Builder methods below ////////////////////////////
+    ///////////////////////////////////////////////////////////////////////////
+
+    /// Set the version of the Android build tools to use for `aapt2`, `d8`,
+    /// `zipalign`, and `apksigner`.
+    ///
+    /// If not set, the version is determined by [`build_tools_dir()`](crate::build_tools_dir).
+    pub fn build_tools_version<S: Into<String>>(&mut self, build_tools_version: S) -> &mut Self {
+        self.build_tools_version = Some(build_tools_version.into());
+        self
+    }
+
+    /// Override the default `android.jar` path.
+    ///
+    /// If not set, it is found using [`android_jar()`](crate::android_jar)
+    /// for the target SDK version, if any.
+    pub fn android_jar<P: Into<PathBuf>>(&mut self, android_jar: P) -> &mut Self {
+        self.android_jar = Some(android_jar.into());
+        self
+    }
+
+    /// Set the `AndroidManifest.xml` file. This is required.
+    pub fn manifest<P: Into<PathBuf>>(&mut self, manifest: P) -> &mut Self {
+        self.manifest = Some(manifest.into());
+        self
+    }
+
+    /// Set the directory of Android resources (`res/`) to be compiled and included.
+    pub fn res_dir<P: Into<PathBuf>>(&mut self, res_dir: P) -> &mut Self {
+        self.res_dir = Some(res_dir.into());
+        self
+    }
+
+    /// Set the directory containing the compiled class files to be dexed. This is required.
+    pub fn classes_dir<P: Into<PathBuf>>(&mut self, classes_dir: P) -> &mut Self {
+        self.classes_dir = Some(classes_dir.into());
+        self
+    }
+
+    /// Set the directory in which intermediate files are placed.
+    ///
+    /// If not set, the directory containing the output APK is used.
+    pub fn work_dir<P: Into<PathBuf>>(&mut self, work_dir: P) -> &mut Self {
+        self.work_dir = Some(work_dir.into());
+        self
+    }
+
+    /// Set the path of the final APK file. This is required.
+    pub fn output_apk<P: Into<PathBuf>>(&mut self, output_apk: P) -> &mut Self {
+        self.output_apk = Some(output_apk.into());
+        self
+    }
+
+    /// Set the minimum Android API level supported by the APK.
+    pub fn min_sdk(&mut self, min_sdk: u32) -> &mut Self {
+        self.min_sdk = Some(min_sdk);
+        self
+    }
+
+    /// Set the Android API level targeted by the APK.
+    pub fn target_sdk(&mut self, target_sdk: u32) -> &mut Self {
+        self.target_sdk = Some(target_sdk);
+        self
+    }
+
+    /// Set the keystore used to sign the APK. This is required unless signing is skipped.
+    pub fn keystore<P: Into<PathBuf>>(&mut self, keystore: P) -> &mut Self {
+        self.keystore = Some(keystore.into());
+        self
+    }
+
+    /// Set the password of the keystore, in the format expected by `apksigner`,
+    /// e.g., `pass:android` or `env:KEYSTORE_PASSWORD`.
+    pub fn keystore_password<S: Into<String>>(&mut self, keystore_password: S) -> &mut Self {
+        self.keystore_password = Some(keystore_password.into());
+        self
+    }
+
+    /// Set the alias of the key within the keystore to sign with.
+    ///
+    /// This is only required if the keystore contains more than one key.
+    pub fn key_alias<S: Into<String>>(&mut self, key_alias: S) -> &mut Self {
+        self.key_alias = Some(key_alias.into());
+        self
+    }
+
+    /// If set to `true`, the APK is aligned but not signed,
+    /// and the `keystore` need not be set.
+    pub fn skip_signing(&mut self, skip_signing: bool) -> &mut Self {
+        self.skip_signing = skip_signing;
+        self
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn packaging_steps() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let dir = crate::TestDir::new("apk");
+        let build_tools = dir.join("sdk").join("build-tools").join("34.0.0");
+        std::fs::create_dir_all(build_tools.join("lib")).unwrap();
+        std::fs::create_dir_all(dir.join("classes")).unwrap();
+        for tool in ["aapt2", "zipalign", "apksigner", "lib/d8.jar"] {
+            std::fs::write(build_tools.join(tool), b"").unwrap();
+        }
+        std::fs::create_dir_all(dir.join("bin")).unwrap();
+        std::fs::write(dir.join("bin").join("javac"), b"").unwrap();
+        env::set_var(env_paths::ANDROID_HOME, dir.join("sdk"));
+        env::remove_var(env_paths::ANDROID_JAVA_HOME);
+        env::set_var(env_paths::JAVA_HOME, &dir);
+
+        let mut apk = AndroidApk::new();
+        apk.build_tools_version("34.0.0")
+            .android_jar(dir.join("android.jar"))
+            .manifest(dir.join("AndroidManifest.xml"))
+            .classes_dir(dir.join("classes"))
+            .output_apk(dir.join("out").join("app.apk"))
+            .min_sdk(21)
+            .skip_signing(true);
+        let unsigned = apk.steps().map(|steps| steps.into_iter()
+            .map(|(step, cmd)| (step, cmd.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect::<Vec<_>>()))
+            .collect::<Vec<_>>()
+        );
+        apk.res_dir(dir.join("res"));
+        let with_res = apk.steps().map(|steps| steps[0].0);
+        apk.skip_signing(false);
+        let no_keystore = apk.steps().map(|_| ());
+        apk.keystore(dir.join("debug.keystore"));
+        let signed = apk.steps().map(|steps| steps.last().map(|(step, _)| *step));
+        env::remove_var(env_paths::JAVA_HOME);
+        env::remove_var(env_paths::ANDROID_HOME);
+
+        let unsigned = unsigned.unwrap();
+        let step_names: Vec<_> = unsigned.iter().map(|(step, _)| *step).collect();
+        assert_eq!(step_names, ["aapt2 link", "d8", "jar", "zipalign"]);
+        let path = |p: PathBuf| p.to_string_lossy().into_owned();
+        let link = &unsigned[0].1;
+        assert_eq!(link[..3], ["link", "-I", &path(dir.join("android.jar"))]);
+        assert!(link.windows(2).any(|w| w == ["--min-sdk-version", "21"]));
+        let d8 = &unsigned[1].1;
+        assert!(d8.windows(2).any(|w| w == ["--min-api", "21"]));
+        assert!(d8.windows(2).any(|w| w == ["--lib", &path(dir.join("android.jar"))]));
+        let zipalign = &unsigned[3].1;
+        assert_eq!(zipalign[zipalign.len() - 2..], [
+            path(dir.join("out").join("unaligned.apk")),
+            path(dir.join("out").join("app.apk")),
+        ]);
+        assert_eq!(with_res.unwrap(), "aapt2 compile");
+        assert!(no_keystore.is_err());
+        assert_eq!(signed.unwrap(), Some("apksigner"));
+    }
+
+    /// Packages a real class into an unsigned APK using the JDK and Android SDK
+    /// found in the environment.
+    #[test]
+    #[cfg(feature = "integration-tests")]
+    fn builds_unsigned_apk() {
+        let dir = crate::TestDir::new("apk-e2e");
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("classes")).unwrap();
+        std::fs::write(
+            dir.join("src").join("Hello.java"),
+            "package rs.robius; public class Hello { public static int answer() { return 42; } }",
+        ).unwrap();
+        std::fs::write(
+            dir.join("AndroidManifest.xml"),
+            r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="rs.robius.hello" />"#,
+        ).unwrap();
+
+        crate::JavaBuild::new()
+            .source_version("8")
+            .target_version("8")
+            .class_path(env_paths::android_jar(None).expect("no android.jar found"))
+            .classes_out_dir(dir.join("classes"))
+            .file(dir.join("src").join("Hello.java"))
+            .compile_checked()
+            .unwrap();
+        AndroidApk::new()
+            .manifest(dir.join("AndroidManifest.xml"))
+            .classes_dir(dir.join("classes"))
+            .output_apk(dir.join("out").join("hello.apk"))
+            .min_sdk(21)
+            .skip_signing(true)
+            .build()
+            .unwrap();
+
+        assert!(dir.join("out").join("hello.apk").is_file());
+        assert!(dir.join("out").join("dex").join("classes.dex").is_file());
+    }
+}
@@ -0,0 +1,160 @@
+//! A single description of the Android platform targeted by a build.
+
+use std::path::PathBuf;
+use crate::env_paths;
+use crate::{AndroidApk, JavaBuild};
+
+/// The Android platform, build tools, and minimum API level targeted by a build,
+/// which can be resolved once into concrete paths and then applied to each builder.
+///
+/// Each field that is `None` is determined by the default resolution logic
+/// and the environment variables documented at the crate level.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AndroidTarget {
+    /// The Android API level to compile against, e.g., `34`.
+    pub api_level: Option<u32>,
+    /// The SDK extension level of the platform, e.g., `4` for `android-33-ext4`.
+    pub extension: Option<u32>,
+    /// The version of the Android build tools, e.g., `34.0.0`.
+    pub build_tools_version: Option<String>,
+    /// The minimum Android API level supported by the build outputs, e.g., `21`.
+    pub min_api: Option<u32>,
+}
+
+/// The concrete paths of an [`AndroidTarget`], as returned by [`AndroidTarget::resolve()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedTarget {
+    /// The `android.jar` file of the targeted platform.
+    pub android_jar: PathBuf,
+    /// The API level of the targeted platform, if it could be determined
+    /// from the name of the platform directory containing `android_jar`.
+    pub api_level: Option<u32>,
+    /// The directory of the selected build tools.
+    pub build_tools_dir: PathBuf,
+    /// The `d8.jar` file within the selected build tools.
+    pub d8_jar: PathBuf,
+    /// The minimum Android API level supported by the build outputs.
+    pub min_api: Option<u32>,
+}
+
+impl AndroidTarget {
+    /// Creates a new `AndroidTarget` from the environment variables documented at the crate level.
+    ///
+    /// Specifically, this sets:
+    /// * the API level and extension, from `ANDROID_PLATFORM` and related variables,
+    ///   including `ANDROID_SDK_EXTENSION`.
+    /// * the build tools version, from `ANDROID_BUILD_TOOLS_VERSION`.
+    /// * the minimum API level, from the API level of the Android target being built by Cargo
+    ///   (see [`cargo_target_api_level()`](crate::cargo_target_api_level)),
+    ///   if the `cargo` feature is enabled.
+    pub fn from_env() -> Self {
+        let platform = env_paths::env_android_platform_api_level()
+            .and_then(|platform| env_paths::platform_version(&platform));
+        #[cfg(feature = "cargo")]
+        let min_api = env_paths::cargo_target_api_level();
+        #[cfg(not(feature = "cargo"))]
+        let min_api = None;
+        Self {
+            api_level: platform.map(|(api_level, _)| api_level),
+            extension: platform.map(|(_, ext)| ext).filter(|&ext| ext != 0),
+            build_tools_version: env_paths::env_var(env_paths::ANDROID_BUILD_TOOLS_VERSION).ok()
+                .filter(|version| !version.is_empty()),
+            min_api,
+        }
+    }
+
+    /// Resolves this target into the concrete paths of its `android.jar`,
+    /// build tools directory, and `d8.jar`.
+    ///
+    /// Returns an error of kind [`std::io::ErrorKind::NotFound`] if any of them cannot be found.
+    pub fn resolve(&self) -> std::io::Result<ResolvedTarget> {
+        let not_found = |msg: String| std::io::Error::new(std::io::ErrorKind::NotFound, msg);
+
+        let android_jar = match self.api_level {
+            Some(api_level) => env_paths::android_jar_for_api(api_level, self.extension),
+            None => env_paths::android_jar(None),
+        }
+        .ok_or_else(|| not_found(format!("Could not find android.jar for {:?}.", self)))?;
+        let api_level = android_jar.parent()
+            .and_then(|platform| platform.file_name()?.to_str())
+            .and_then(env_paths::platform_version)
+            .map(|(api_level, _)| api_level)
+            .or(self.api_level);
+
+        let build_tools_dir = env_paths::build_tools_dir(self.build_tools_version.as_deref())
+            .ok_or_else(|| not_found(format!("Could not find build tools for {:?}.", self)))?;
+        // Look up `d8.jar` in the exact build tools chosen above, without falling back again.
+        let d8_jar = env_paths::android_d8_jar(build_tools_dir.file_name().and_then(|v| v.to_str()))
+            .ok_or_else(|| not_found(format!("Could not find d8.jar in {:?}.", build_tools_dir)))?;
+
+        Ok(ResolvedTarget {
+            android_jar,
+            api_level,
+            build_tools_dir,
+            d8_jar,
+            min_api: self.min_api,
+        })
+    }
+}
+
+impl ResolvedTarget {
+    /// Configures the given [`JavaBuild`] to compile against this target's `android.jar`,
+    /// which is added as a class path entry.
+    pub fn apply_to_java_build<'b>(&self, java_build: &'b mut JavaBuild) -> &'b mut JavaBuild {
+        java_build.class_path(&self.android_jar)
+    }
+
+    /// Configures the given [`AndroidApk`] to use this target's `android.jar`,
+    /// build tools, API level (as the target SDK), and minimum API level.
+    pub fn apply_to_android_apk<'a>(&self, android_apk: &'a mut AndroidApk) -> &'a mut AndroidApk {
+        android_apk.android_jar(&self.android_jar);
+        if let Some(version) = self.build_tools_dir.file_name().and_then(|v| v.to_str()) {
+            android_apk.build_tools_version(version);
+        }
+        if let Some(api_level) = self.api_level {
+            android_apk.target_sdk(api_level);
+        }
+        if let Some(min_api) = self.min_api {
+            android_apk.min_sdk(min_api);
+        }
+        android_apk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn resolves_paths_in_sdk() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = crate::TestDir::new("sdk-target");
+        for platform in ["android-33", "android-33-ext4", "android-34"] {
+            std::fs::create_dir_all(sdk.join("platforms").join(platform)).unwrap();
+            std::fs::write(sdk.join("platforms").join(platform).join("android.jar"), b"").unwrap();
+        }
+        for version in ["33.0.1", "34.0.0"] {
+            std::fs::create_dir_all(sdk.join("build-tools").join(version).join("lib")).unwrap();
+            std::fs::write(sdk.join("build-tools").join(version).join("lib").join("d8.jar"), b"").unwrap();
+        }
+        env::set_var(env_paths::ANDROID_HOME, &sdk);
+        let target = AndroidTarget {
+            api_level: Some(33),
+            extension: Some(4),
+            build_tools_version: Some("33.0.1".into()),
+            min_api: Some(21),
+        };
+        let resolved = target.resolve();
+        let missing = AndroidTarget { api_level: Some(30), ..target.clone() }.resolve();
+        env::remove_var(env_paths::ANDROID_HOME);
+
+        let resolved = resolved.unwrap();
+        assert_eq!(resolved.android_jar, sdk.join("platforms").join("android-33-ext4").join("android.jar"));
+        assert_eq!(resolved.api_level, Some(33));
+        assert_eq!(resolved.build_tools_dir, sdk.join("build-tools").join("33.0.1"));
+        assert_eq!(resolved.d8_jar, sdk.join("build-tools").join("33.0.1").join("lib").join("d8.jar"));
+        assert_eq!(resolved.min_api, Some(21));
+        assert_eq!(missing.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+    }
+}
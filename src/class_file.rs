@@ -0,0 +1,60 @@
+//! Reading metadata from the header of compiled Java `.class` files.
+
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The magic number at the start of every Java class file.
+const CLASS_FILE_MAGIC: [u8; 4] = [0xCA, 0xFE, 0xBA, 0xBE];
+
+/// Returns the major version of the given Java `.class` file,
+/// e.g., `52` for a class compiled for Java 8, or `65` for Java 21.
+///
+/// Only the first 8 bytes of the file are read. An error of kind
+/// [`io::ErrorKind::InvalidData`] is returned if the file does not start
+/// with the class file magic number `0xCAFEBABE`.
+///
+/// The major version of a class file is its Java version plus 44;
+/// classes that are too new for the DEX compiler in use are a common cause
+/// of errors when building Android apps.
+pub fn class_file_major_version<P: AsRef<Path>>(path: P) -> io::Result<u16> {
+    let mut header = [0u8; 8];
+    std::fs::File::open(path.as_ref())?.read_exact(&mut header)?;
+    if header[..4] != CLASS_FILE_MAGIC {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} is not a Java class file.", path.as_ref().display()),
+        ));
+    }
+    // Bytes 4-5 hold the minor version, and bytes 6-7 the major version.
+    Ok(u16::from_be_bytes([header[6], header[7]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes the given bytes to a new file with the given `name`,
+    /// within a test directory that is removed when dropped.
+    fn write_file(name: &str, contents: &[u8]) -> (crate::TestDir, std::path::PathBuf) {
+        let dir = crate::TestDir::new(name);
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn reads_major_version() {
+        let (_dir, path) = write_file("Java8.class", &[0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 52, 0x00]);
+        assert_eq!(class_file_major_version(&path).unwrap(), 52);
+        let (_dir, path) = write_file("Java21.class", &[0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 65]);
+        assert_eq!(class_file_major_version(&path).unwrap(), 65);
+    }
+
+    #[test]
+    fn rejects_non_class_files() {
+        let (_dir, path) = write_file("NotAClass.class", b"public class A {}");
+        assert_eq!(class_file_major_version(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let (_dir, path) = write_file("Truncated.class", &[0xCA, 0xFE, 0xBA, 0xBE, 0x00]);
+        assert_eq!(class_file_major_version(&path).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
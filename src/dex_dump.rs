@@ -0,0 +1,151 @@
+//! Builder for customizing and invoking a `dexdump` command.
+
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Output};
+use crate::env_paths::{self, PathExt};
+
+/// A builder for a `dexdump` command that can be invoked
+/// to inspect the contents of DEX files.
+///
+/// If you need to customize the `dexdump` command beyond what is provided here,
+/// you can use the [`DexDump::command()`] method to get a [`Command`]
+/// that can be further customized with additional arguments.
+#[derive(Clone, Debug, Default)]
+pub struct DexDump {
+    /// Override the default path to the `dexdump` executable.
+    /// Otherwise, it is found in the Android SDK's build tools directory.
+    dexdump_path: Option<PathBuf>,
+
+    /// The version of the Android build tools in which to find `dexdump`.
+    build_tools_version: Option<String>,
+
+    /// If `true`, display summary information from the file header ("-f" flag).
+    file_headers: bool,
+
+    /// If `true`, disassemble the code sections ("-d" flag).
+    disassemble: bool,
+
+    /// Paths to the DEX (or APK) files to be inspected.
+    files: Vec<OsString>,
+}
+
+impl DexDump {
+    /// Creates a new `DexDump` instance with default values,
+    /// which can be further customized using the builder methods.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Executes the `dexdump` command based on this `DexDump` instance.
+    pub fn run(&self) -> std::io::Result<ExitStatus> {
+        self.command()?.status()
+    }
+
+    /// Executes the `dexdump` command based on this `DexDump` instance,
+    /// capturing its stdout and stderr instead of inheriting them.
+    ///
+    /// The dumped contents are available in the `stdout` field of the returned [`Output`].
+    pub fn output(&self) -> std::io::Result<Output> {
+        self.command()?.output()
+    }
+
+    /// Returns a [`Command`] based on this `DexDump` instance
+    /// that can be inspected or customized before being executed.
+    pub fn command(&self) -> std::io::Result<Command> {
+        let dexdump_clone = self.dexdump_path.clone();
+        let dexdump = dexdump_clone
+            .and_then(PathExt::path_if_exists)
+            .or_else(|| env_paths::dexdump(self.build_tools_version.as_deref()))
+            .ok_or_else(|| std::io::Error::other(
+                "dexdump not provided, and could not be found in the Android SDK build tools."
+            ))?;
+
+        let mut cmd = Command::new(dexdump);
+
+        for (flag, cond) in [
+            ("-f", self.file_headers),
+            ("-d", self.disassemble),
+        ].into_iter() {
+            if cond { cmd.arg(flag); }
+        }
+
+        self.files.iter().for_each(|f| { cmd.arg(f); });
+
+        Ok(cmd)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    //////////////////////// Builder methods below ////////////////////////////
+    ///////////////////////////////////////////////////////////////////////////
+
+    /// Override the default path to the `dexdump` executable.
+    ///
+    /// If not set, `dexdump` is found in the Android SDK's build tools directory.
+    pub fn dexdump_path<P: Into<PathBuf>>(&mut self, dexdump_path: P) -> &mut Self {
+        self.dexdump_path = Some(dexdump_path.into());
+        self
+    }
+
+    /// Set the version of the Android build tools in which to find `dexdump`.
+    ///
+    /// If not set, the version is determined by [`build_tools_dir()`](crate::build_tools_dir).
+    pub fn build_tools_version<S: Into<String>>(&mut self, build_tools_version: S) -> &mut Self {
+        self.build_tools_version = Some(build_tools_version.into());
+        self
+    }
+
+    /// If set to `true`, display summary information from the file header.
+    #[doc(alias("-f"))]
+    pub fn file_headers(&mut self, file_headers: bool) -> &mut Self {
+        self.file_headers = file_headers;
+        self
+    }
+
+    /// If set to `true`, disassemble the code sections.
+    #[doc(alias("-d"))]
+    pub fn disassemble(&mut self, disassemble: bool) -> &mut Self {
+        self.disassemble = disassemble;
+        self
+    }
+
+    /// Adds a DEX (or APK) file to be inspected.
+    pub fn file<P: AsRef<OsStr>>(&mut self, file: P) -> &mut Self {
+        self.files.push(file.as_ref().into());
+        self
+    }
+
+    /// Adds multiple DEX (or APK) files to be inspected.
+    ///
+    /// This is the same as calling [`DexDump::file()`] multiple times.
+    pub fn files<P>(&mut self, files: P) -> &mut Self
+    where
+        P: IntoIterator,
+        P::Item: AsRef<OsStr>,
+    {
+        self.files.extend(files.into_iter().map(|f| f.as_ref().into()));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_dexdump_in_build_tools() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = crate::TestDir::new("sdk-dexdump");
+        let build_tools = sdk.join("build-tools").join("34.0.0");
+        std::fs::create_dir_all(&build_tools).unwrap();
+        let dexdump = build_tools.join(format!("dexdump{}", std::env::consts::EXE_SUFFIX));
+        std::fs::write(&dexdump, b"").unwrap();
+        std::env::set_var(env_paths::ANDROID_HOME, &sdk);
+        let cmd = DexDump::new().build_tools_version("34.0.0").file_headers(true).file("classes.dex").command();
+        std::env::remove_var(env_paths::ANDROID_HOME);
+
+        let cmd = cmd.unwrap();
+        assert_eq!(cmd.get_program(), dexdump);
+        assert_eq!(cmd.get_args().collect::<Vec<_>>(), ["-f", "classes.dex"]);
+    }
+}
@@ -0,0 +1,180 @@
+//! Parsing of the diagnostics printed by `javac`.
+
+use std::path::PathBuf;
+
+/// The severity of a [`Diagnostic`] reported by `javac`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    /// A note, e.g., `Note: Recompile with -Xlint:unchecked for details.`
+    Note,
+}
+
+/// A single error, warning, or note reported by `javac`.
+///
+/// See [`JavaBuild::compile_diagnostics()`](crate::JavaBuild::compile_diagnostics)
+/// and [`parse_javac_diagnostics()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The source file that the diagnostic refers to, if any.
+    pub file: Option<PathBuf>,
+    /// The 1-based line number within `file`, if any.
+    pub line: Option<u32>,
+    /// The 1-based column within `line`, as indicated by `javac`'s `^` marker, if any.
+    pub column: Option<u32>,
+    pub severity: Severity,
+    /// The diagnostic message. Messages that span multiple lines
+    /// (e.g., the `symbol:` and `location:` details of a missing symbol)
+    /// are joined with newlines, without the quoted source line and `^` marker.
+    pub message: String,
+}
+
+/// Parses the diagnostics from the given `javac` output (i.e., its stderr),
+/// which are in the standard `path:line: error: message` form.
+///
+/// Diagnostics without a source location (e.g., `warning: [options] ...`
+/// or `Note: ...`) are included with `file` and `line` set to `None`.
+/// The summary lines at the end of the output (e.g., `1 error`) are ignored.
+pub fn parse_javac_diagnostics(output: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut current: Option<Diagnostic> = None;
+    // Whether the quoted source line and the `^` marker line (if any) have been seen
+    // for the current diagnostic, after which all lines are part of its message.
+    let mut seen_source = false;
+    let mut seen_caret = false;
+
+    for line in output.lines() {
+        if let Some(diagnostic) = parse_header(line) {
+            seen_source = diagnostic.file.is_none();
+            seen_caret = diagnostic.file.is_none();
+            diagnostics.extend(current.replace(diagnostic));
+            continue;
+        }
+        if is_summary(line) {
+            diagnostics.extend(current.take());
+            continue;
+        }
+        let Some(diagnostic) = current.as_mut() else { continue };
+        if !seen_caret && seen_source && line.trim() == "^" {
+            diagnostic.column = line.find('^').and_then(|i| u32::try_from(i + 1).ok());
+            seen_caret = true;
+        } else if !seen_source {
+            seen_source = true;
+        } else {
+            seen_caret = true;
+            diagnostic.message.push('\n');
+            diagnostic.message.push_str(line.trim());
+        }
+    }
+    diagnostics.extend(current);
+    diagnostics
+}
+
+/// Parses the first line of a diagnostic, e.g., `Foo.java:3: error: message`,
+/// `warning: [options] message`, or `Note: message`.
+fn parse_header(line: &str) -> Option<Diagnostic> {
+    let located = [(": error: ", Severity::Error), (": warning: ", Severity::Warning)]
+        .into_iter()
+        .find_map(|(marker, severity)| {
+            let (location, message) = line.split_once(marker)?;
+            let (file, line_num) = location.rsplit_once(':')?;
+            Some(Diagnostic {
+                file: Some(PathBuf::from(file)),
+                line: Some(line_num.parse().ok()?),
+                column: None,
+                severity,
+                message: message.trim().to_string(),
+            })
+        });
+    located.or_else(|| {
+        let (severity, message) = [("error: ", Severity::Error), ("warning: ", Severity::Warning), ("Note: ", Severity::Note)]
+            .into_iter()
+            .find_map(|(prefix, severity)| Some((severity, line.strip_prefix(prefix)?)))?;
+        Some(Diagnostic {
+            file: None,
+            line: None,
+            column: None,
+            severity,
+            message: message.trim().to_string(),
+        })
+    })
+}
+
+/// Returns `true` if the given line is one of the summary lines
+/// that `javac` prints last, e.g., `1 error` or `3 warnings`.
+fn is_summary(line: &str) -> bool {
+    let mut words = line.split_whitespace();
+    matches!(
+        (words.next().map(|n| n.parse::<u32>()), words.next(), words.next()),
+        (Some(Ok(_)), Some("error" | "errors" | "warning" | "warnings"), None)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_located_diagnostics_with_columns() {
+        let output = "\
+src/C.java:2: error: incompatible types: String cannot be converted to int
+  int x = \"s\";
+          ^
+src/C.java:3: warning: [removal] Foo in bar has been deprecated
+  Foo y;
+  ^
+1 error
+1 warning
+";
+        assert_eq!(parse_javac_diagnostics(output), [
+            Diagnostic {
+                file: Some("src/C.java".into()),
+                line: Some(2),
+                column: Some(11),
+                severity: Severity::Error,
+                message: "incompatible types: String cannot be converted to int".into(),
+            },
+            Diagnostic {
+                file: Some("src/C.java".into()),
+                line: Some(3),
+                column: Some(3),
+                severity: Severity::Warning,
+                message: "[removal] Foo in bar has been deprecated".into(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn joins_multi_line_messages() {
+        let output = "\
+C:\\src\\C.java:3: error: cannot find symbol
+  void f() { Foo y; }
+             ^
+  symbol:   class Foo
+  location: class C
+1 error
+";
+        let diagnostics = parse_javac_diagnostics(output);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, Some(PathBuf::from("C:\\src\\C.java")));
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert_eq!(diagnostics[0].column, Some(14));
+        assert_eq!(diagnostics[0].message, "cannot find symbol\nsymbol:   class Foo\nlocation: class C");
+    }
+
+    #[test]
+    fn parses_diagnostics_without_location() {
+        let output = "\
+warning: [options] bootstrap class path not set in conjunction with -source 8
+Note: C.java uses unchecked or unsafe operations.
+Note: Recompile with -Xlint:unchecked for details.
+1 warning
+";
+        let diagnostics = parse_javac_diagnostics(output);
+        let severities = diagnostics.iter().map(|d| d.severity).collect::<Vec<_>>();
+        assert_eq!(severities, [Severity::Warning, Severity::Note, Severity::Note]);
+        assert!(diagnostics.iter().all(|d| d.file.is_none() && d.line.is_none() && d.column.is_none()));
+        assert_eq!(diagnostics[1].message, "C.java uses unchecked or unsafe operations.");
+    }
+}
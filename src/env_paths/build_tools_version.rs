@@ -0,0 +1,100 @@
+//! Parsing and ordering of Android build tools version strings.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A parsed Android build tools version, e.g., `34.0.0` or `34.0.0-rc2`.
+///
+/// Versions are ordered numerically by their `major.minor.micro` components,
+/// and a final release is ordered after all of its release candidates,
+/// e.g., `33.0.1 < 34.0.0-rc1 < 34.0.0-rc2 < 34.0.0 < 34.0.1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BuildToolsVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub micro: u32,
+    /// The release candidate number, or `None` for a final release.
+    pub rc: Option<u32>,
+}
+
+impl Ord for BuildToolsVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.micro).cmp(&(other.major, other.minor, other.micro))
+            .then_with(|| match (self.rc, other.rc) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(&b),
+            })
+    }
+}
+impl PartialOrd for BuildToolsVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BuildToolsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.micro)?;
+        if let Some(rc) = self.rc {
+            write!(f, "-rc{}", rc)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses an Android build tools version string, such as the name
+/// of a subdirectory of the SDK's `build-tools` directory.
+///
+/// Missing `minor` or `micro` components are treated as `0`, e.g., `34` is `34.0.0`.
+/// Any suffix after a `-` is treated as a pre-release, e.g., `34.0.0-rc2`;
+/// a suffix without a number (e.g., `-preview`) is treated as release candidate `0`.
+///
+/// Returns `None` if the string doesn't start with a numeric major version.
+pub fn parse_build_tools_version(version: &str) -> Option<BuildToolsVersion> {
+    let (numbers, suffix) = match version.trim().split_once('-') {
+        Some((numbers, suffix)) => (numbers, Some(suffix)),
+        None => (version.trim(), None),
+    };
+    let mut components = numbers.split('.').map(str::parse::<u32>);
+    let major = components.next()?.ok()?;
+    let minor = components.next().unwrap_or(Ok(0)).ok()?;
+    let micro = components.next().unwrap_or(Ok(0)).ok()?;
+    let rc = suffix.map(|s| s
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .parse::<u32>()
+        .unwrap_or(0)
+    );
+    Some(BuildToolsVersion { major, minor, micro, rc })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> BuildToolsVersion {
+        parse_build_tools_version(s).unwrap()
+    }
+
+    #[test]
+    fn parses_partial_and_rc_versions() {
+        assert_eq!(version("34"), BuildToolsVersion { major: 34, minor: 0, micro: 0, rc: None });
+        assert_eq!(version("33.0.1"), BuildToolsVersion { major: 33, minor: 0, micro: 1, rc: None });
+        assert_eq!(version("34.0.0-rc2"), BuildToolsVersion { major: 34, minor: 0, micro: 0, rc: Some(2) });
+        assert_eq!(version("35.0.0-preview").rc, Some(0));
+        assert_eq!(parse_build_tools_version("latest"), None);
+        assert_eq!(parse_build_tools_version("34.x"), None);
+    }
+
+    #[test]
+    fn orders_versions_numerically_with_rc_before_release() {
+        let mut versions = ["34.0.1", "4.0", "34.0.0", "33.0.2", "34.0.0-rc2", "34.0.0-rc1", "10.0.0"]
+            .map(version);
+        versions.sort();
+        assert_eq!(
+            versions.map(|v| v.to_string()),
+            ["4.0.0", "10.0.0", "33.0.2", "34.0.0-rc1", "34.0.0-rc2", "34.0.0", "34.0.1"],
+        );
+    }
+}
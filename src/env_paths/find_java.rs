@@ -1,5 +1,5 @@
 
-use std::{path::PathBuf, process::Command};
+use std::{path::{Path, PathBuf}, process::Command};
 
 #[cfg(target_os = "macos")]
 const FIND_CMD: &str = "/usr/libexec/java_home";
@@ -22,12 +22,12 @@ pub fn find_java_home() -> Option<PathBuf> {
     }
 
     let cmd_output = command.output().map_err(|error| {
-        eprintln!("Command '{FIND_CMD}' not found. Error: {error}");
+        log_warn!("Command '{FIND_CMD}' not found. Error: {error}");
     }).ok()?;
     let found_java_path = String::from_utf8(cmd_output.stdout).ok().map(|output| {
         let lines = output.lines().collect::<Vec<&str>>();
         if lines.len() > 1 {
-            eprintln!("Using the last of {} discovered Java locations:\n\t{}",
+            log_warn!("Using the last of {} discovered Java locations:\n\t{}",
                 lines.len(),
                 lines.join("\n\t"),
             );
@@ -38,7 +38,7 @@ pub fn find_java_home() -> Option<PathBuf> {
     })?;
 
     if found_java_path.is_empty() {
-        eprintln!("Java is not installed, or is missing from the system PATH.");
+        log_warn!("Java is not installed, or is missing from the system PATH.");
         return None;
     }
 
@@ -62,5 +62,214 @@ pub fn find_java_home() -> Option<PathBuf> {
         java_path.pop();
     }
 
+    log_debug!("Discovered Java home via '{FIND_CMD}': {}", java_path.display());
     Some(java_path)
 }
+
+/// Directories that commonly contain multiple side-by-side JDK installations.
+#[cfg(target_os = "windows")]
+const JDK_INSTALL_ROOTS: &[&str] = &[
+    r"C:\Program Files\Java",
+    r"C:\Program Files\Eclipse Adoptium",
+    r"C:\Program Files\Microsoft",
+    r"C:\Program Files\Zulu",
+];
+
+/// Directories that commonly contain multiple side-by-side JDK installations.
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+const JDK_INSTALL_ROOTS: &[&str] = &[
+    "/usr/lib/jvm",
+    "/usr/lib64/jvm",
+    "/usr/java",
+];
+
+/// Attempts to find the home directory of a JDK with the given major version,
+/// e.g., `17` for JDK 17 or `8` for JDK 1.8.
+///
+/// * On macOS, this invokes `/usr/libexec/java_home -v <major>`.
+/// * On Linux and Windows, this scans common JDK installation directories
+///   (e.g., `/usr/lib/jvm` or `C:\Program Files\Java`) for a directory
+///   whose name contains the major version, such as `jdk-17.0.2` or `java-17-openjdk-amd64`.
+#[cfg(target_os = "macos")]
+pub fn find_java_home_version(major: u32) -> Option<PathBuf> {
+    let cmd_output = Command::new(FIND_CMD)
+        .arg("-v")
+        .arg(major.to_string())
+        .output()
+        .map_err(|error| {
+            log_warn!("Command '{FIND_CMD}' not found. Error: {error}");
+        })
+        .ok()?;
+    if !cmd_output.status.success() {
+        log_warn!("No Java installation found for version {major}.");
+        return None;
+    }
+    let found_java_path = String::from_utf8(cmd_output.stdout).ok()?;
+    let java_path = PathBuf::from(found_java_path.trim());
+    java_path.is_dir().then_some(java_path)
+}
+
+/// Attempts to find the home directory of a JDK with the given major version,
+/// e.g., `17` for JDK 17 or `8` for JDK 1.8.
+///
+/// * On macOS, this invokes `/usr/libexec/java_home -v <major>`.
+/// * On Linux and Windows, this scans common JDK installation directories
+///   (e.g., `/usr/lib/jvm` or `C:\Program Files\Java`) for a directory
+///   whose name contains the major version, such as `jdk-17.0.2` or `java-17-openjdk-amd64`.
+#[cfg(not(target_os = "macos"))]
+pub fn find_java_home_version(major: u32) -> Option<PathBuf> {
+    JDK_INSTALL_ROOTS.iter()
+        .find_map(|root| find_jdk_in_dir(Path::new(root), major))
+        .inspect(|jh| log_debug!("Discovered JDK {major} at {}", jh.display()))
+}
+
+/// Scans the given directory for a JDK installation with the given major version.
+///
+/// If there are multiple matches, the last one in sorted order is used,
+/// which is usually the one with the highest minor/patch version.
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+fn find_jdk_in_dir(dir: &Path, major: u32) -> Option<PathBuf> {
+    let mut candidates = std::fs::read_dir(dir).ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name()
+            .to_str()
+            .is_some_and(|name| dir_name_matches_major(name, major))
+        )
+        .map(|entry| entry.path())
+        .filter(|path| path.join("bin").is_dir())
+        .collect::<Vec<_>>();
+    candidates.sort();
+    candidates.pop()
+}
+
+/// Returns `true` if the given JDK directory name refers to the given major version.
+///
+/// This handles names like `jdk-17`, `jdk-17.0.2`, `java-17-openjdk-amd64`,
+/// `jdk1.8.0_202`, and `java-1.8.0-openjdk`.
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+fn dir_name_matches_major(name: &str, major: u32) -> bool {
+    name.split(|c: char| !c.is_ascii_digit() && c != '.')
+        .filter(|segment| !segment.is_empty())
+        .any(|version| {
+            let mut parts = version.split('.');
+            let first = parts.next().and_then(|p| p.parse::<u32>().ok());
+            match first {
+                Some(1) => parts.next().and_then(|p| p.parse::<u32>().ok()) == Some(major),
+                Some(v) => v == major,
+                None => false,
+            }
+        })
+}
+
+/// Returns the common locations of the JetBrains Runtime (JBR) bundled with Android Studio.
+#[cfg(target_os = "macos")]
+fn android_studio_jbr_candidates() -> Vec<PathBuf> {
+    let mut app_dirs = vec![PathBuf::from("/Applications")];
+    if let Ok(home) = std::env::var("HOME") {
+        app_dirs.push(Path::new(&home).join("Applications"));
+    }
+    app_dirs.into_iter()
+        .flat_map(|dir| ["jbr", "jre"].map(|jbr| dir
+            .join("Android Studio.app")
+            .join("Contents")
+            .join(jbr)
+            .join("Contents")
+            .join("Home")
+        ))
+        .collect()
+}
+
+/// Returns the common locations of the JetBrains Runtime (JBR) bundled with Android Studio.
+#[cfg(target_os = "windows")]
+fn android_studio_jbr_candidates() -> Vec<PathBuf> {
+    let mut studio_dirs = vec![PathBuf::from(r"C:\Program Files\Android\Android Studio")];
+    if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+        studio_dirs.push(Path::new(&local_app_data).join("Programs").join("Android Studio"));
+    }
+    studio_dirs.into_iter()
+        .flat_map(|dir| ["jbr", "jre"].map(|jbr| dir.join(jbr)))
+        .collect()
+}
+
+/// Returns the common locations of the JetBrains Runtime (JBR) bundled with Android Studio.
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn android_studio_jbr_candidates() -> Vec<PathBuf> {
+    let mut studio_dirs = vec![
+        PathBuf::from("/opt/android-studio"),
+        PathBuf::from("/usr/local/android-studio"),
+        PathBuf::from("/snap/android-studio/current/android-studio"),
+    ];
+    if let Ok(home) = std::env::var("HOME") {
+        studio_dirs.push(Path::new(&home).join("android-studio"));
+    }
+    studio_dirs.into_iter()
+        .flat_map(|dir| ["jbr", "jre"].map(|jbr| dir.join(jbr)))
+        .collect()
+}
+
+/// Attempts to find the JetBrains Runtime (JBR) that is bundled with Android Studio,
+/// which is a full JDK that includes `javac`.
+///
+/// This is useful on machines where Android Studio's JBR is the only JDK installed.
+pub fn find_android_studio_jbr() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    const JAVAC: &str = "javac.exe";
+    #[cfg(not(target_os = "windows"))]
+    const JAVAC: &str = "javac";
+
+    android_studio_jbr_candidates().into_iter()
+        .find(|jbr| jbr.join("bin").join(JAVAC).is_file())
+        .inspect(|jbr| log_debug!("Discovered Android Studio's bundled JDK at {}", jbr.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_jdk_dir_names() {
+        for name in ["jdk-17", "jdk-17.0.2", "java-17-openjdk-amd64", "temurin-17.jdk"] {
+            assert!(dir_name_matches_major(name, 17), "{name}");
+        }
+        for name in ["jdk1.8.0_202", "java-1.8.0-openjdk"] {
+            assert!(dir_name_matches_major(name, 8), "{name}");
+        }
+        assert!(!dir_name_matches_major("jdk-17.0.2", 2));
+        assert!(!dir_name_matches_major("java-11-openjdk", 1));
+        assert!(!dir_name_matches_major("default-java", 17));
+    }
+
+    #[test]
+    fn finds_latest_matching_jdk_in_dir() {
+        let dir = crate::TestDir::new("jvm");
+        for jdk in ["jdk-17.0.1", "jdk-17.0.9", "jdk-21.0.1"] {
+            std::fs::create_dir_all(dir.join(jdk).join("bin")).unwrap();
+        }
+        std::fs::create_dir_all(dir.join("jdk-11-no-bin")).unwrap();
+
+        assert_eq!(find_jdk_in_dir(&dir, 17), Some(dir.join("jdk-17.0.9")));
+        assert_eq!(find_jdk_in_dir(&dir, 21), Some(dir.join("jdk-21.0.1")));
+        assert_eq!(find_jdk_in_dir(&dir, 11), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn finds_android_studio_jbr_in_home() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let home = crate::TestDir::new("studio-home");
+        let jbr = home.join("android-studio").join("jbr");
+        std::fs::create_dir_all(jbr.join("bin")).unwrap();
+        let old_home = std::env::var_os("HOME");
+        std::env::set_var("HOME", &home);
+        let candidates = android_studio_jbr_candidates();
+        std::fs::write(jbr.join("bin").join("javac"), b"").unwrap();
+        let found = find_android_studio_jbr();
+        match old_home {
+            Some(old_home) => std::env::set_var("HOME", old_home),
+            None => std::env::remove_var("HOME"),
+        }
+
+        assert!(candidates.contains(&jbr));
+        assert!(found.is_some());
+    }
+}
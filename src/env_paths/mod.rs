@@ -1,8 +1,19 @@
-use std::{env, path::{Path, PathBuf}};
-use self::find_java::find_java_home;
+use std::{env, ffi::OsString, path::{Path, PathBuf}, process::Command};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use self::find_java::{find_java_home, find_android_studio_jbr};
+pub use self::find_java::find_java_home_version;
+pub use self::resolved_paths::ResolvedPaths;
+pub use self::build_tools_version::{BuildToolsVersion, parse_build_tools_version};
+pub use self::resolution::ResolutionSource;
+pub use self::toolchain_report::toolchain_report;
 
+mod build_tools_version;
 mod find_android_sdk;
 mod find_java;
+mod resolution;
+mod resolved_paths;
+mod toolchain_report;
 
 
 pub const ANDROID_HOME:                 &str = "ANDROID_HOME";
@@ -12,11 +23,36 @@ pub const ANDROID_PLATFORM:             &str = "ANDROID_PLATFORM";
 pub const ANDROID_SDK_VERSION:          &str = "ANDROID_SDK_VERSION";
 pub const ANDROID_API_LEVEL:            &str = "ANDROID_API_LEVEL";
 pub const ANDROID_SDK_EXTENSION:        &str = "ANDROID_SDK_EXTENSION";
+pub const ANDROID_COMPILE_SDK:          &str = "ANDROID_COMPILE_SDK";
+pub const COMPILE_SDK_VERSION:          &str = "COMPILE_SDK_VERSION";
 pub const ANDROID_D8_JAR:               &str = "ANDROID_D8_JAR";
 pub const ANDROID_JAR:                  &str = "ANDROID_JAR";
+pub const ANDROID_AAPT2:                &str = "ANDROID_AAPT2";
+pub const ANDROID_ZIPALIGN:             &str = "ANDROID_ZIPALIGN";
+pub const ANDROID_APKSIGNER:            &str = "ANDROID_APKSIGNER";
+pub const ADB:                          &str = "ADB";
 pub const JAVA_HOME:                    &str = "JAVA_HOME";
+pub const ANDROID_JAVA_HOME:            &str = "ANDROID_JAVA_HOME";
 pub const JAVA_SOURCE_VERSION:          &str = "JAVA_SOURCE_VERSION";
 pub const JAVA_TARGET_VERSION:          &str = "JAVA_TARGET_VERSION";
+pub const CLASSPATH:                    &str = "CLASSPATH";
+pub const ANDROID_STRICT:               &str = "ANDROID_STRICT";
+pub const JAVA_OPTS:                    &str = "JAVA_OPTS";
+pub const ANDROID_JAVAC_OPTS:           &str = "ANDROID_JAVAC_OPTS";
+pub const ANDROID_NDK_HOME:             &str = "ANDROID_NDK_HOME";
+pub const ANDROID_NDK_ROOT:             &str = "ANDROID_NDK_ROOT";
+
+/// The file name of the `javac` executable on the current platform.
+#[cfg(target_os = "windows")]
+const JAVAC_EXE: &str = "javac.exe";
+#[cfg(not(target_os = "windows"))]
+const JAVAC_EXE: &str = "javac";
+
+/// The separator between entries in a class path on the current platform.
+#[cfg(target_os = "windows")]
+pub(crate) const CLASSPATH_SEPARATOR: &str = ";";
+#[cfg(not(target_os = "windows"))]
+pub(crate) const CLASSPATH_SEPARATOR: &str = ":";
 
 /// An extension trait for checking if a path exists.
 pub trait PathExt {
@@ -34,6 +70,38 @@ impl<P: AsRef<Path>> PathExt for P {
     }
 }
 
+/// Reads the given environment variable.
+///
+/// If the `cargo` feature is enabled, this also instructs Cargo to re-run
+/// the build script if that variable changes. This is not done otherwise, because
+/// printing any `rerun-if` line disables Cargo's default of re-running
+/// the build script whenever any file in the package changes.
+pub(crate) fn env_var(key: &str) -> Result<String, env::VarError> {
+    #[cfg(feature = "cargo")]
+    println!("cargo:rerun-if-env-changed={}", key);
+    env::var(key)
+}
+
+/// Cleans up a path read from an environment variable by removing
+/// surrounding whitespace, one pair of surrounding quotes (e.g., `"C:\Android\Sdk"`),
+/// and any trailing path separators (e.g., `/path/to/sdk/`).
+///
+/// A root path like `/` or `C:\` is left intact.
+fn normalize_env_path(value: String) -> String {
+    let mut path = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(unquoted) = path.strip_prefix(quote).and_then(|p| p.strip_suffix(quote)) {
+            path = unquoted.trim();
+            break;
+        }
+    }
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    if trimmed.is_empty() || trimmed.ends_with(':') {
+        path.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
 
 /// Returns the path to the Android SDK directory.
 ///
@@ -46,13 +114,28 @@ impl<P: AsRef<Path>> PathExt for P {
 ///   * On Linux, this is `~/Android/Sdk`.
 #[doc(alias("ANDROID_HOME", "ANDROID_SDK_ROOT", "home", "sdk", "root"))]
 pub fn android_sdk() -> Option<PathBuf> {
-    env::var(ANDROID_HOME).ok()
+    env_var(ANDROID_HOME).ok()
+        .map(normalize_env_path)
         .and_then(PathExt::path_if_exists)
-        .or_else(|| env::var(ANDROID_SDK_ROOT).ok()
+        .or_else(|| env_var(ANDROID_SDK_ROOT).ok()
+            .map(normalize_env_path)
             .and_then(PathExt::path_if_exists)
         )
         .map(PathBuf::from)
-        .or_else(|| find_android_sdk::find_android_sdk().and_then(PathExt::path_if_exists))
+        .inspect(|sdk| log_debug!("Using Android SDK from env var: {}", sdk.display()))
+        .or_else(|| find_android_sdk::find_android_sdk()
+            .and_then(PathExt::path_if_exists)
+            .inspect(|sdk| log_debug!("Using Android SDK at default location: {}", sdk.display()))
+        )
+}
+
+/// Returns `true` if an Android SDK directory can be found using [`android_sdk()`].
+///
+/// This is a cheap check that does not spawn any processes,
+/// which allows build scripts to gracefully skip Android-specific work
+/// on machines without the Android SDK.
+pub fn is_sdk_available() -> bool {
+    android_sdk().is_some()
 }
 
 /// Returns the path to the `android.jar` file for the given API level.
@@ -68,58 +151,607 @@ pub fn android_sdk() -> Option<PathBuf> {
 /// * `ANDROID_PLATFORM`
 /// * `ANDROID_API_LEVEL`
 /// * `ANDROID_SDK_VERSION`
+/// * `ANDROID_COMPILE_SDK`
+/// * `COMPILE_SDK_VERSION`
 /// * `ANDROID_SDK_EXTENSION`
+///
+/// The first five are consulted in the above order of precedence,
+/// and `ANDROID_SDK_EXTENSION` is then applied to the chosen value.
+///
+/// If none of those are set and the `cargo` feature is enabled, the API level
+/// of the Android target being built by Cargo is used, if it can be determined
+/// (see [`cargo_target_api_level()`]).
+///
+/// If none of those are set either, the latest installed platform is used.
+///
+/// If the platform string includes an SDK extension (e.g., `android-33-ext4`)
+/// but that platform is not installed, the base platform (e.g., `android-33`) is used instead.
 pub fn android_jar(platform_string: Option<&str>) -> Option<PathBuf> {
-    env::var(ANDROID_JAR).ok()
+    android_jar_resolved(platform_string).map(|(jar, _source)| jar)
+}
+
+/// Returns the path to the `android.jar` file for the given numeric API level
+/// and optional SDK extension level, e.g., `android-33-ext4` for `(33, Some(4))`.
+///
+/// If `extension` is `None`, the `ANDROID_SDK_EXTENSION` environment variable is used, if set.
+/// If the platform for the requested extension is not installed,
+/// the base platform (e.g., `android-33`) is used instead.
+///
+/// Otherwise, this behaves like [`android_jar()`], including its handling
+/// of the `ANDROID_JAR` environment variable.
+pub fn android_jar_for_api(api_level: u32, extension: Option<u32>) -> Option<PathBuf> {
+    let extension = extension.or_else(|| env_var(ANDROID_SDK_EXTENSION).ok()
+        .as_deref()
+        .and_then(normalize_sdk_extension)
+    );
+    let platform = match extension {
+        Some(ext) => format!("android-{api_level}-ext{ext}"),
+        None => format!("android-{api_level}"),
+    };
+    android_jar(Some(&platform))
+}
+
+/// Same as [`android_jar()`], but also returns where the `android.jar` path came from.
+pub fn android_jar_resolved(platform_string: Option<&str>) -> Option<(PathBuf, ResolutionSource)> {
+    env_var(ANDROID_JAR).ok()
+        .map(normalize_env_path)
         .and_then(PathExt::path_if_exists)
-        .map(PathBuf::from)
-        .or_else(|| android_sdk()
-            .and_then(|sdk| sdk
-                .join("platforms")
-                .join(platform_string.map(ToString::to_string)
-                    .unwrap_or_else(|| env_android_platform_api_level()
-                        .expect("either ANDROID_JAR or [ANDROID_PLATFORM, ANDROID_API_LEVEL, ANDROID_SDK_VERSION] must be set")
-                    )
-                )
+        .map(|jar| (PathBuf::from(jar), ResolutionSource::EnvVar))
+        .inspect(|(jar, _)| log_debug!("Using android.jar from {ANDROID_JAR}: {}", jar.display()))
+        .or_else(|| {
+            let (platform, source) = selected_platform_dir(platform_string)?;
+            platform
                 .join("android.jar")
                 .path_if_exists()
-            )
-        )
+                .inspect(|jar| log_debug!("Using android.jar from the Android SDK: {}", jar.display()))
+                .map(|jar| (jar, source))
+        })
+}
+
+/// Returns the name of the Android platform (e.g., `android-34` or `android-33-ext4`)
+/// from which [`android_jar()`] would take the `android.jar` file,
+/// if no explicit platform string were given.
+///
+/// This is useful for logging which platform was actually chosen,
+/// especially when falling back to the latest installed platform.
+/// Note that the `ANDROID_JAR` environment variable is not considered here.
+pub fn resolved_platform() -> Option<String> {
+    selected_platform_dir(None)?
+        .0
+        .file_name()?
+        .to_str()
+        .map(String::from)
+}
+
+/// Returns the name of the build tools version (e.g., `34.0.0`)
+/// that [`build_tools_dir()`] and [`android_d8_jar()`] would use,
+/// if no explicit build tools version were given.
+///
+/// This is useful for logging which version was actually chosen,
+/// especially when falling back to the latest installed build tools.
+/// Note that the `ANDROID_D8_JAR` environment variable is not considered here.
+pub fn resolved_build_tools() -> Option<String> {
+    build_tools_dir(None)?
+        .file_name()?
+        .to_str()
+        .map(String::from)
+}
+
+/// Returns the directory of the Android platform to be used by [`android_jar()`],
+/// following the precedence documented there, along with where it came from.
+fn selected_platform_dir(platform_string: Option<&str>) -> Option<(PathBuf, ResolutionSource)> {
+    let platforms = android_sdk()?.join("platforms");
+    let requested = platform_string.map(|plat| (plat.to_string(), ResolutionSource::ExplicitArg))
+        .or_else(|| env_android_platform_api_level().map(|plat| (plat, ResolutionSource::EnvPlatform)))
+        .or_else(|| cargo_target_platform().map(|plat| (plat, ResolutionSource::CargoTarget)));
+    match requested {
+        Some((platform, source)) => platform_dir(&platforms, &platform)
+            .map(|dir| (dir, source)),
+        None if is_strict_mode() => {
+            log_warn!("No Android platform specified, and {ANDROID_STRICT} forbids using the latest installed one.");
+            None
+        }
+        None => find_latest_platform(&platforms)
+            .inspect(|plat| log_warn!(
+                "No Android platform specified, using the latest installed platform: {}",
+                plat.display(),
+            ))
+            .map(|dir| (dir, ResolutionSource::LatestFallback)),
+    }
+}
+
+/// Returns the directory for the given `platform` within the SDK's `platforms` directory.
+///
+/// If the `platform` includes an SDK extension (e.g., `android-33-ext4`)
+/// but that extension-specific directory doesn't exist,
+/// this falls back to the base platform directory (e.g., `android-33`).
+fn platform_dir(platforms: &Path, platform: &str) -> Option<PathBuf> {
+    platforms.join(platform).path_if_exists()
+        .or_else(|| {
+            let (base, _ext) = platform.split_once("-ext")?;
+            platforms.join(base).path_if_exists()
+                .inspect(|dir| crate::process::build_warning(&format!(
+                    "Android platform '{platform}' not found, falling back to the base platform: {}",
+                    dir.display(),
+                )))
+        })
 }
 
 /// Returns the path to the `d8.jar` file for the given build tools version.
 ///
 /// If the `ANDROID_D8_JAR` environment variable is set and points to a file that exists,
 /// that path is returned.
-/// If `build_tools_version`is `None`, the value of the `ANDROID_BUILD_TOOLS_VERSION` environment variable is used
-/// to find the `d8.jar` file from the Android SDK root directory.
+/// Otherwise, the `d8.jar` file is found within the build tools directory
+/// given by [`build_tools_dir()`] for the given `build_tools_version`.
 pub fn android_d8_jar(build_tools_version: Option<&str>) -> Option<PathBuf> {
-    env::var(ANDROID_D8_JAR).ok()
+    android_d8_jar_resolved(build_tools_version).map(|(jar, _source)| jar)
+}
+
+/// Same as [`android_d8_jar()`], but also returns where the `d8.jar` path came from.
+pub fn android_d8_jar_resolved(build_tools_version: Option<&str>) -> Option<(PathBuf, ResolutionSource)> {
+    env_var(ANDROID_D8_JAR).ok()
+        .map(normalize_env_path)
         .and_then(PathExt::path_if_exists)
-        .map(PathBuf::from)
-        .or_else(|| android_sdk()
-            .and_then(|sdk| sdk
-                .join("build-tools")
-                .join(build_tools_version.map(ToString::to_string)
-                    .unwrap_or_else(|| env::var(ANDROID_BUILD_TOOLS_VERSION)
-                        .expect("either ANDROID_D8_JAR or ANDROID_BUILD_TOOLS_VERSION must be set")
-                    )
-                )
-                .join("lib")
+        .map(|jar| (PathBuf::from(jar), ResolutionSource::EnvVar))
+        .inspect(|(jar, _)| log_debug!("Using d8.jar from {ANDROID_D8_JAR}: {}", jar.display()))
+        .or_else(|| {
+            let (bt, source) = build_tools_dir_resolved(build_tools_version)?;
+            bt.join("lib")
                 .join("d8.jar")
                 .path_if_exists()
+                .inspect(|jar| log_debug!("Using d8.jar from the Android SDK: {}", jar.display()))
+                .map(|jar| (jar, source))
+        })
+}
+
+/// Returns the path to the Android SDK's build tools directory for the given version,
+/// which contains tools like `aapt2`, `zipalign`, `apksigner`, and `d8`.
+///
+/// The version is determined by an ordered set of attempts:
+/// * The given `build_tools_version`, if it is `Some`.
+/// * The `ANDROID_BUILD_TOOLS_VERSION` environment variable, if it is set.
+/// * The latest version installed in the Android SDK's `build-tools` directory.
+///
+/// A partial version like `34` or `34.0` selects the latest installed build tools
+/// whose version starts with it (e.g., `34.0.1`), unless a directory with that exact name exists.
+///
+/// The resulting directory is only returned if it exists.
+#[doc(alias("ANDROID_BUILD_TOOLS_VERSION", "build-tools"))]
+pub fn build_tools_dir(build_tools_version: Option<&str>) -> Option<PathBuf> {
+    build_tools_dir_resolved(build_tools_version).map(|(dir, _source)| dir)
+}
+
+/// Same as [`build_tools_dir()`], but also returns where the build tools version came from.
+pub fn build_tools_dir_resolved(build_tools_version: Option<&str>) -> Option<(PathBuf, ResolutionSource)> {
+    let build_tools = android_sdk()?.join("build-tools");
+    // An empty version (e.g., from an unfilled CI template) is treated as unset;
+    // otherwise, it would select the `build-tools` directory itself.
+    let requested = build_tools_version.map(|v| (v.trim().to_string(), ResolutionSource::ExplicitArg))
+        .filter(|(v, _)| !v.is_empty())
+        .or_else(|| env_var(ANDROID_BUILD_TOOLS_VERSION).ok()
+            .map(|v| (v.trim().to_string(), ResolutionSource::EnvBuildTools))
+            .filter(|(v, _)| !v.is_empty())
+        );
+    match requested {
+        Some((version, source)) => build_tools.join(&version).path_if_exists()
+            .or_else(|| find_latest_version_with_prefix(&build_tools, &version)
+                .inspect(|bt| log_debug!(
+                    "Build tools version '{version}' matched installed build tools: {}",
+                    bt.display(),
+                ))
             )
+            .map(|dir| (dir, source)),
+        None if is_strict_mode() => {
+            log_warn!("{ANDROID_BUILD_TOOLS_VERSION} not set, and {ANDROID_STRICT} forbids using the latest installed build tools.");
+            None
+        }
+        None => find_latest_version(&build_tools)
+            .inspect(|bt| log_warn!(
+                "{ANDROID_BUILD_TOOLS_VERSION} not set, using the latest installed build tools: {}",
+                bt.display(),
+            ))
+            .map(|dir| (dir, ResolutionSource::LatestFallback)),
+    }
+}
+
+/// Returns the exact revision of the build tools in the given directory,
+/// e.g., `34.0.0` or `34.0.0-rc1`, as given by `Pkg.Revision` in its `source.properties` file.
+///
+/// This is more reliable than the directory's name, which may have been renamed.
+/// A release candidate suffix like `34.0.0 rc1` is normalized to `34.0.0-rc1`,
+/// such that the result can be compared via [`parse_build_tools_version()`].
+///
+/// Returns an error of kind [`std::io::ErrorKind::NotFound`] if `source.properties`
+/// does not exist, or of kind [`std::io::ErrorKind::InvalidData`]
+/// if it does not contain a `Pkg.Revision`.
+pub fn check_build_tools_version(build_tools_dir: &Path) -> std::io::Result<String> {
+    let properties_file = build_tools_dir.join("source.properties");
+    let properties = std::fs::read_to_string(&properties_file).map_err(|e| std::io::Error::new(
+        e.kind(),
+        format!("Could not read {:?}: {e}", properties_file),
+    ))?;
+    parse_pkg_revision(&properties).ok_or_else(|| std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("No Pkg.Revision found in {:?}", properties_file),
+    ))
+}
+
+/// Parses the `Pkg.Revision` value from the contents of a `source.properties` file.
+fn parse_pkg_revision(properties: &str) -> Option<String> {
+    let revision = properties.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#') && !line.starts_with('!'))
+        .find_map(|line| {
+            let (key, value) = line.split_once(['=', ':'])?;
+            (key.trim() == "Pkg.Revision").then(|| value.trim())
+        })
+        .filter(|revision| !revision.is_empty())?;
+    let mut parts = revision.split_whitespace();
+    let numbers = parts.next()?;
+    Some(match parts.next() {
+        Some(suffix) => format!("{numbers}-{suffix}"),
+        None => numbers.to_string(),
+    })
+}
+
+/// Returns the path to the `dexdump` tool for the given build tools version.
+///
+/// The build tools directory is found using [`build_tools_dir()`].
+pub fn dexdump(build_tools_version: Option<&str>) -> Option<PathBuf> {
+    build_tools_dir(build_tools_version)?
+        .join(format!("dexdump{}", env::consts::EXE_SUFFIX))
+        .path_if_exists()
+}
+
+/// Returns the path to the `aapt2` tool for the given build tools version.
+///
+/// If the `ANDROID_AAPT2` environment variable is set and points to a file that exists,
+/// that path is returned.
+/// Otherwise, the build tools directory is found using [`build_tools_dir()`].
+pub fn aapt2(build_tools_version: Option<&str>) -> Option<PathBuf> {
+    tool_from_env(ANDROID_AAPT2).or_else(|| build_tools_dir(build_tools_version)?
+        .join(format!("aapt2{}", env::consts::EXE_SUFFIX))
+        .path_if_exists()
+    )
+}
+
+/// Returns the path to the `zipalign` tool for the given build tools version.
+///
+/// If the `ANDROID_ZIPALIGN` environment variable is set and points to a file that exists,
+/// that path is returned.
+/// Otherwise, the build tools directory is found using [`build_tools_dir()`].
+pub fn zipalign(build_tools_version: Option<&str>) -> Option<PathBuf> {
+    tool_from_env(ANDROID_ZIPALIGN).or_else(|| build_tools_dir(build_tools_version)?
+        .join(format!("zipalign{}", env::consts::EXE_SUFFIX))
+        .path_if_exists()
+    )
+}
+
+/// Returns the path to the `apksigner` tool for the given build tools version.
+///
+/// If the `ANDROID_APKSIGNER` environment variable is set and points to a file that exists,
+/// that path is returned.
+/// Otherwise, the build tools directory is found using [`build_tools_dir()`].
+/// Note that `apksigner` is a script, so on Windows this is `apksigner.bat`.
+pub fn apksigner(build_tools_version: Option<&str>) -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    const APKSIGNER: &str = "apksigner.bat";
+    #[cfg(not(target_os = "windows"))]
+    const APKSIGNER: &str = "apksigner";
+
+    tool_from_env(ANDROID_APKSIGNER).or_else(|| build_tools_dir(build_tools_version)?
+        .join(APKSIGNER)
+        .path_if_exists()
+    )
+}
+
+/// Returns the path to the `adb` tool.
+///
+/// If the `ADB` environment variable is set and points to a file that exists,
+/// that path is returned.
+/// Otherwise, `adb` is found in the directory given by [`platform_tools_dir()`].
+pub fn adb() -> Option<PathBuf> {
+    tool_from_env(ADB).or_else(|| platform_tools_dir()?
+        .join(format!("adb{}", env::consts::EXE_SUFFIX))
+        .path_if_exists()
+    )
+}
+
+/// Returns the path given by the environment variable `key`, if it exists.
+fn tool_from_env(key: &str) -> Option<PathBuf> {
+    env_var(key).ok()
+        .map(normalize_env_path)
+        .and_then(PathExt::path_if_exists)
+        .map(PathBuf::from)
+        .inspect(|tool| log_debug!("Using tool from {key}: {}", tool.display()))
+}
+
+/// Returns the path to the Android emulator, i.e., `emulator/emulator` in the Android SDK.
+pub fn emulator() -> Option<PathBuf> {
+    android_sdk()?
+        .join("emulator")
+        .join(format!("emulator{}", env::consts::EXE_SUFFIX))
+        .path_if_exists()
+}
+
+/// Returns the names of all Android Virtual Devices (AVDs) known to the emulator.
+///
+/// This runs `emulator -list-avds` and parses its output.
+pub fn list_avds() -> std::io::Result<Vec<String>> {
+    let emulator = emulator().ok_or_else(|| std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "Could not find the Android emulator in the Android SDK.",
+    ))?;
+    let output = Command::new(emulator).arg("-list-avds").output()?;
+    crate::process::check_status("emulator", output.status, Some(&output.stderr))?;
+    Ok(parse_avd_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parses the output of `emulator -list-avds` into a list of AVD names.
+///
+/// AVD names cannot contain whitespace, so any line that does
+/// (e.g., `INFO | ...` log messages printed by the emulator) is skipped.
+fn parse_avd_list(output: &str) -> Vec<String> {
+    output.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.contains(char::is_whitespace))
+        .map(String::from)
+        .collect()
+}
+
+/// Returns the path to the Android SDK's `platform-tools` directory,
+/// which contains tools like `adb`.
+pub fn platform_tools_dir() -> Option<PathBuf> {
+    android_sdk()?
+        .join("platform-tools")
+        .path_if_exists()
+}
+
+/// Returns the value of the `PATH` environment variable with the `bin` directory
+/// of the given `java_home`, the SDK's `platform-tools` directory, and the
+/// selected `build-tools` directory prepended to it, if they exist.
+///
+/// Returns `None` if the resulting paths cannot be joined, e.g., if one contains
+/// the platform's path separator.
+pub(crate) fn augmented_path(java_home: &Path) -> Option<OsString> {
+    let extra_dirs = [
+        java_home.join("bin").path_if_exists(),
+        platform_tools_dir(),
+        build_tools_dir(None),
+    ];
+    let existing = env::var_os("PATH").unwrap_or_default();
+    env::join_paths(extra_dirs.into_iter()
+        .flatten()
+        .chain(env::split_paths(&existing))
+    ).ok()
+}
+
+/// Returns the path to the `bin` directory of the Android SDK's command-line tools,
+/// which contains tools like `sdkmanager` and `avdmanager`.
+///
+/// The `cmdline-tools/latest/bin` directory is preferred if it exists.
+/// Otherwise, the highest-versioned `cmdline-tools/<version>/bin` directory is used.
+pub fn cmdline_tools_dir() -> Option<PathBuf> {
+    let cmdline_tools = android_sdk()?.join("cmdline-tools");
+    cmdline_tools.join("latest").join("bin").path_if_exists()
+        .or_else(|| find_latest_version(&cmdline_tools)
+            .and_then(|ver| ver.join("bin").path_if_exists())
         )
 }
 
+/// Returns the path to the `sdkmanager` tool within [`cmdline_tools_dir()`].
+///
+/// Note that `sdkmanager` is a script, so on Windows this is `sdkmanager.bat`.
+pub fn sdkmanager() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    const SDKMANAGER: &str = "sdkmanager.bat";
+    #[cfg(not(target_os = "windows"))]
+    const SDKMANAGER: &str = "sdkmanager";
+
+    cmdline_tools_dir()?
+        .join(SDKMANAGER)
+        .path_if_exists()
+}
+
+/// Returns the paths of all packages installed in the Android SDK,
+/// e.g., `build-tools;34.0.0` or `platforms;android-34`.
+///
+/// This runs `sdkmanager --list_installed` and parses its output.
+pub fn list_installed_packages() -> std::io::Result<Vec<String>> {
+    let sdkmanager = sdkmanager().ok_or_else(|| std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "Could not find sdkmanager in the Android SDK's command-line tools.",
+    ))?;
+    let output = Command::new(sdkmanager).arg("--list_installed").output()?;
+    crate::process::check_status("sdkmanager", output.status, Some(&output.stderr))?;
+    Ok(parse_installed_packages(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Returns the subset of the `required` SDK packages that are not installed,
+/// e.g., `["platforms;android-34"]`.
+///
+/// Each missing package can be installed by running `sdkmanager '<package>'`.
+pub fn missing_sdk_packages<'a>(required: &[&'a str]) -> std::io::Result<Vec<&'a str>> {
+    let installed = list_installed_packages()?;
+    Ok(required.iter()
+        .copied()
+        .filter(|pkg| !installed.iter().any(|i| i == pkg))
+        .collect())
+}
+
+/// Parses the table printed by `sdkmanager --list_installed`,
+/// returning the value of the first ("Path") column in each row.
+fn parse_installed_packages(output: &str) -> Vec<String> {
+    output.lines()
+        .filter_map(|line| line.split_once('|'))
+        .map(|(path, _rest)| path.trim())
+        .filter(|path| !path.is_empty() && *path != "Path" && !path.starts_with('-'))
+        .map(String::from)
+        .collect()
+}
+
+/// Returns `true` if the `ANDROID_STRICT` environment variable is set to `1` or `true`.
+///
+/// In strict mode, functions like [`android_jar()`] and [`android_d8_jar()`] never fall back
+/// to the latest installed platform or build tools, and instead return `None`
+/// if no version was specified, which keeps builds reproducible.
+pub fn is_strict_mode() -> bool {
+    env_var(ANDROID_STRICT).is_ok_and(|strict| {
+        let strict = strict.trim();
+        strict == "1" || strict.eq_ignore_ascii_case("true")
+    })
+}
+
+/// Returns the path to the Android NDK directory.
+///
+/// The path is determined by an ordered set of attempts:
+/// * The `ANDROID_NDK_HOME` environment variable, if it is set and if the directory exists.
+/// * The `ANDROID_NDK_ROOT` environment variable, if it is set and if the directory exists.
+/// * The highest-versioned `ndk/<version>` directory in the Android SDK.
+/// * The legacy `ndk-bundle` directory in the Android SDK.
+#[doc(alias("ANDROID_NDK_HOME", "ANDROID_NDK_ROOT", "ndk"))]
+pub fn android_ndk() -> Option<PathBuf> {
+    tool_from_env(ANDROID_NDK_HOME)
+        .or_else(|| tool_from_env(ANDROID_NDK_ROOT))
+        .or_else(|| {
+            let sdk = android_sdk()?;
+            find_latest_version(&sdk.join("ndk"))
+                .or_else(|| sdk.join("ndk-bundle").path_if_exists())
+                .inspect(|ndk| log_debug!("Using Android NDK from the Android SDK: {}", ndk.display()))
+        })
+}
+
+/// Returns the path to the NDK's prebuilt `clang` wrapper for the given target and API level,
+/// e.g., `toolchains/llvm/prebuilt/linux-x86_64/bin/aarch64-linux-android21-clang`.
+///
+/// The `target` may be either a Rust target triple (e.g., `armv7-linux-androideabi`)
+/// or the NDK's own name for it (e.g., `armv7a-linux-androideabi`).
+/// The NDK is found using [`android_ndk()`], and the prebuilt toolchain for the
+/// current host (`linux-x86_64`, `darwin-x86_64`, or `windows-x86_64`) is used.
+/// On Windows, the wrapper is a `.cmd` script.
+pub fn ndk_toolchain_clang(target: &str, api_level: u32) -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    const HOST_TAG: &str = "windows-x86_64";
+    #[cfg(target_os = "macos")]
+    const HOST_TAG: &str = "darwin-x86_64";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    const HOST_TAG: &str = "linux-x86_64";
+    #[cfg(target_os = "windows")]
+    const WRAPPER_SUFFIX: &str = ".cmd";
+    #[cfg(not(target_os = "windows"))]
+    const WRAPPER_SUFFIX: &str = "";
+
+    let clang_target = match target {
+        "armv7-linux-androideabi" | "thumbv7neon-linux-androideabi" | "arm-linux-androideabi"
+            => "armv7a-linux-androideabi",
+        other => other,
+    };
+    android_ndk()?
+        .join("toolchains").join("llvm").join("prebuilt").join(HOST_TAG).join("bin")
+        .join(format!("{clang_target}{api_level}-clang{WRAPPER_SUFFIX}"))
+        .path_if_exists()
+}
+
+/// Returns the subdirectory of `dir` with the highest version number that starts with
+/// the given partial version, e.g., `34.0.1` for a `prefix` of `34` or `34.0`.
+///
+/// The prefix must match whole version components, so `3` does not match `34.0.0`.
+fn find_latest_version_with_prefix(dir: &Path, prefix: &str) -> Option<PathBuf> {
+    std::fs::read_dir(dir).ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let rest = name.strip_prefix(prefix)?;
+            if !(rest.starts_with('.') || rest.starts_with('-')) {
+                return None;
+            }
+            let version = parse_build_tools_version(&name)?;
+            Some((version, entry.path()))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, path)| path)
+}
+
+/// Returns the subdirectory of `dir` with the highest version number in its name,
+/// e.g., `34.0.0` is chosen over `33.0.2`, `4.0`, and `34.0.0-rc2`.
+///
+/// Versions are compared using [`BuildToolsVersion`]'s ordering;
+/// subdirectories whose names don't start with a number are ignored.
+fn find_latest_version(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir).ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let version = parse_build_tools_version(entry.file_name().to_str()?)?;
+            Some((version, entry.path()))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, path)| path)
+}
+
+/// Returns the subdirectory of `platforms` for the latest installed Android platform,
+/// e.g., `android-34` is chosen over both `android-33-ext4` and `android-9`.
+///
+/// Preview platforms without a numeric API level (e.g., `android-UpsideDownCake`) are ignored.
+fn find_latest_platform(platforms: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(platforms).ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| Some((platform_version(entry.file_name().to_str()?)?, entry.path())))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, path)| path)
+}
+
+/// Parses a platform directory name like `android-34` or `android-33-ext4`
+/// into a sortable `(api_level, extension)` pair, e.g., `(34, 0)` or `(33, 4)`.
+pub(crate) fn platform_version(name: &str) -> Option<(u32, u32)> {
+    match name.strip_prefix("android-")?.split_once("-ext") {
+        Some((api_level, ext)) => Some((api_level.parse().ok()?, ext.parse().ok()?)),
+        None => Some((name.strip_prefix("android-")?.parse().ok()?, 0)),
+    }
+}
+
+/// Returns the name and `android.jar` path of every platform installed in the Android SDK,
+/// e.g., `("android-34", ".../platforms/android-34/android.jar")`.
+///
+/// The platforms are sorted from oldest to newest API level, with SDK extensions
+/// sorted after their base platform. Preview platforms without a numeric API level
+/// (e.g., `android-UpsideDownCake`) are sorted last, by name.
+/// Returns an empty list if the Android SDK cannot be found.
+pub fn android_jar_all() -> Vec<(String, PathBuf)> {
+    let Some(platforms) = android_sdk().map(|sdk| sdk.join("platforms")) else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(platforms) else {
+        return Vec::new();
+    };
+    let mut jars: Vec<(String, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let jar = entry.path().join("android.jar").path_if_exists()?;
+            Some((name, jar))
+        })
+        .collect();
+    jars.sort_by_cached_key(|(name, _)| (
+        platform_version(name).unwrap_or((u32::MAX, u32::MAX)),
+        name.clone(),
+    ));
+    jars
+}
+
 /// Returns the platform version string (aka API level, SDK version) being targeted for compilation.
 ///
 /// This deals with environment variables `ANDROID_PLATFORM`, `ANDROID_API_LEVEL`, and `ANDROID_SDK_VERSION`,
+/// followed by the Gradle-style aliases `ANDROID_COMPILE_SDK` and `COMPILE_SDK_VERSION`,
 /// as well as the optional `ANDROID_SDK_EXTENSION`.
-fn env_android_platform_api_level() -> Option<String> {
-    let mut base = env::var(ANDROID_PLATFORM).ok()
-        .or_else(|| env::var(ANDROID_API_LEVEL).ok())
-        .or_else(|| env::var(ANDROID_SDK_VERSION).ok())?;
+pub(crate) fn env_android_platform_api_level() -> Option<String> {
+    let mut base = env_var(ANDROID_PLATFORM).ok()
+        .or_else(|| env_var(ANDROID_API_LEVEL).ok())
+        .or_else(|| env_var(ANDROID_SDK_VERSION).ok())
+        .or_else(|| env_var(ANDROID_COMPILE_SDK).ok())
+        .or_else(|| env_var(COMPILE_SDK_VERSION).ok())?;
     
     if base.is_empty() {
         return None;
@@ -133,18 +765,66 @@ fn env_android_platform_api_level() -> Option<String> {
         return Some(base);
     }
 
-    if let Some(raw_ext) = env::var(ANDROID_SDK_EXTENSION).ok() {
-        let ext_num = raw_ext
-            .trim_start_matches("-")
-            .trim_start_matches("ext");
-        if !ext_num.is_empty() {
-            base = format!("{}-ext{}", base, ext_num);
-        }
+    if let Some(ext_num) = env_var(ANDROID_SDK_EXTENSION).ok()
+        .as_deref()
+        .and_then(normalize_sdk_extension)
+    {
+        base = format!("{}-ext{}", base, ext_num);
     }
     
     Some(base)
 }
 
+/// Parses an SDK extension level from any of its common spellings,
+/// i.e., `-ext4`, `ext4`, or just `4`, all of which yield `Some(4)`.
+///
+/// This is how the `ANDROID_SDK_EXTENSION` environment variable is interpreted,
+/// and can be used to build a platform string like `android-33-ext4`.
+/// Returns `None` if `raw` is empty or not a valid extension level.
+pub fn normalize_sdk_extension(raw: &str) -> Option<u32> {
+    let raw = raw.trim();
+    let raw = raw.strip_prefix('-').unwrap_or(raw);
+    let digits = raw.strip_prefix("ext").unwrap_or(raw);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Returns the Android API level of the target currently being built by Cargo.
+///
+/// This is only meaningful within a build script, and returns `None` unless
+/// `CARGO_CFG_TARGET_OS` is `android`. The API level is determined from:
+/// * The `CARGO_NDK_ANDROID_PLATFORM` environment variable, as set by `cargo-ndk`.
+/// * The name of the linker given by `RUSTC_LINKER`, which for the NDK's clang wrappers
+///   includes the API level, e.g., `aarch64-linux-android21-clang`.
+#[cfg(feature = "cargo")]
+pub fn cargo_target_api_level() -> Option<u32> {
+    if env::var("CARGO_CFG_TARGET_OS").ok()? != "android" {
+        return None;
+    }
+    env_var("CARGO_NDK_ANDROID_PLATFORM").ok()
+        .and_then(|platform| platform.trim_start_matches("android-").parse().ok())
+        .or_else(|| {
+            let linker = PathBuf::from(env_var("RUSTC_LINKER").ok()?);
+            let name = linker.file_stem()?.to_str()?;
+            let prefix = name.strip_suffix("-clang").or_else(|| name.strip_suffix("-clang++"))?;
+            let digits = prefix.len() - prefix.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+            prefix[prefix.len() - digits ..].parse().ok()
+        })
+        .inspect(|api_level| log_debug!("Using API level {api_level} of the Cargo build target"))
+}
+
+/// Returns the platform string (e.g., `android-34`) for the Cargo build target's API level.
+fn cargo_target_platform() -> Option<String> {
+    #[cfg(feature = "cargo")] {
+        cargo_target_api_level().map(|api_level| format!("android-{api_level}"))
+    }
+    #[cfg(not(feature = "cargo"))] {
+        None
+    }
+}
+
 /// Returns the path to the `java` executable by looking for `$JAVA_HOME/bin/java`.
 pub fn java() -> Option<PathBuf> {
     java_home().and_then(|jh| jh
@@ -165,25 +845,668 @@ pub fn javac() -> Option<PathBuf> {
 
 /// Returns the JAVA_HOME path by attempting to discover it.
 /// 
-/// First, if the `$JAVA_HOME` environment variable is set and points to a directory that exists,
-/// that path is returned.
-/// Otherwise, a series of common installation locations is used,
-/// based on the current platform (macOS, Linux, Windows).
+/// The path is determined by an ordered set of attempts:
+/// * The `ANDROID_JAVA_HOME` environment variable, if it is set and if the directory exists.
+///   This allows Android builds to use a different JDK than the rest of the system.
+/// * The `JAVA_HOME` environment variable, if it is set and if the directory exists.
+/// * A series of common installation locations, based on the current platform (macOS, Linux, Windows).
+/// * The JDK bundled with Android Studio (the JetBrains Runtime), if Android Studio
+///   is installed in a common location.
 pub fn java_home() -> Option<PathBuf> {
-    env::var(JAVA_HOME).ok()
+    java_home_from_env()
+        .or_else(find_java_home)
+        .or_else(find_android_studio_jbr)
+}
+
+/// Returns the Java home directory given by `ANDROID_JAVA_HOME` or `JAVA_HOME`, if it exists.
+fn java_home_from_env() -> Option<PathBuf> {
+    env_var(ANDROID_JAVA_HOME).ok()
+        .map(normalize_env_path)
         .and_then(PathExt::path_if_exists)
         .map(PathBuf::from)
-        .or_else(find_java_home)
+        .inspect(|jh| log_debug!("Using Java home from {ANDROID_JAVA_HOME}: {}", jh.display()))
+        .or_else(|| env_var(JAVA_HOME).ok()
+            .map(normalize_env_path)
+            .and_then(PathExt::path_if_exists)
+            .map(PathBuf::from)
+            .inspect(|jh| log_debug!("Using Java home from {JAVA_HOME}: {}", jh.display()))
+        )
+}
+
+/// Returns the home directory of the first JDK that can be found
+/// from the given list of preferred major versions, in order of preference.
+///
+/// Each version is looked up using [`find_java_home_version()`].
+/// If none of the preferred versions are installed, this falls back to [`java_home()`].
+///
+/// This is useful for deterministically choosing a JDK on machines with several JDKs installed,
+/// e.g., `java_home_preferring(&[17, 21])` to prefer JDK 17 over JDK 21.
+pub fn java_home_preferring(versions: &[u32]) -> Option<PathBuf> {
+    versions.iter()
+        .find_map(|&major| find_java_home_version(major))
+        .or_else(|| {
+            log_warn!("None of the preferred JDK versions {versions:?} were found, using the default Java home.");
+            java_home()
+        })
+}
+
+/// Returns the JAVA_HOME path of a full JDK, i.e., one that contains the `javac` compiler.
+///
+/// This first discovers the Java home directory using [`java_home()`].
+/// If that directory is a JRE that lacks `bin/javac`, but is nested within a JDK
+/// (e.g., `$JDK/jre` in Java 8 and earlier), the enclosing JDK directory is returned instead.
+/// Otherwise, if no `javac` can be found, `None` is returned.
+pub fn java_home_with_javac() -> Option<PathBuf> {
+    java_home().and_then(|jh| jdk_dir_with_javac(&jh))
+}
+
+/// Returns the given Java home directory or its parent (e.g., for a JRE within a JDK),
+/// whichever contains `bin/javac`.
+fn jdk_dir_with_javac(java_home: &Path) -> Option<PathBuf> {
+    java_home
+        .ancestors()
+        .take(2)
+        .find(|dir| dir.join("bin").join(JAVAC_EXE).path_if_exists().is_some())
+        .map(Path::to_path_buf)
+}
+
+/// Returns `true` if a JDK containing the `javac` compiler can be found.
+///
+/// This is a cheap check that does not spawn any processes,
+/// which allows build scripts to gracefully skip Java compilation
+/// on machines without a JDK. It checks for `javac` in:
+/// * the directory given by `ANDROID_JAVA_HOME` or `JAVA_HOME`,
+/// * the directories in the `PATH` environment variable, and
+/// * the JDK bundled with Android Studio.
+///
+/// Unlike [`java_home_with_javac()`], this does not run the platform's
+/// Java locator command (e.g., `/usr/libexec/java_home` on macOS).
+pub fn is_jdk_available() -> bool {
+    java_home_from_env().and_then(|jh| jdk_dir_with_javac(&jh)).is_some()
+        || env::var_os("PATH").is_some_and(|path| env::split_paths(&path)
+            .any(|dir| dir.join(JAVAC_EXE).is_file())
+        )
+        || find_android_studio_jbr().is_some()
+}
+
+/// Same as [`check_javac_version()`], but only runs `javac -version` once per Java home
+/// directory for the lifetime of the current process, returning `None` if that failed.
+///
+/// This allows builders to validate versions every time a command is built
+/// (e.g., for each root of a parallel build) without spawning `javac` each time.
+pub(crate) fn cached_javac_version(java_home: &Path) -> Option<u32> {
+    static JAVAC_VERSIONS: OnceLock<Mutex<HashMap<PathBuf, Option<u32>>>> = OnceLock::new();
+    let cache = JAVAC_VERSIONS.get_or_init(Default::default);
+    if let Some(version) = cache.lock().unwrap_or_else(PoisonError::into_inner).get(java_home) {
+        return *version;
+    }
+    // Don't hold the lock while running `javac`, so that other Java homes aren't blocked.
+    let version = check_javac_version(java_home).ok();
+    cache.lock().unwrap_or_else(PoisonError::into_inner).insert(java_home.to_path_buf(), version);
+    version
+}
+
+/// Returns the major version of the `javac` compiler in the given Java home directory,
+/// e.g., `8` for Java 1.8 or `17` for Java 17.
+///
+/// This runs `javac -version` and parses its output.
+pub fn check_javac_version(java_home: &Path) -> std::io::Result<u32> {
+    let output = Command::new(java_home.join("bin").join("javac"))
+        .arg("-version")
+        .output()?;
+    // Older JDKs print the version to stderr rather than stdout,
+    // and banners like `Picked up JAVA_TOOL_OPTIONS` may appear on either stream,
+    // so both are combined and then filtered for meaningful lines.
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr),
+    );
+    parse_javac_version_output(&text).ok_or_else(|| std::io::Error::other(
+        format!("Failed to parse the output of `javac -version`: {:?}", text)
+    ))
+}
+
+/// Returns the major version of the `java` launcher in the given Java home directory,
+/// e.g., `8` for Java 1.8 or `17` for Java 17.
+///
+/// This runs `java -version` and parses its output.
+pub fn check_java_version(java_home: &Path) -> std::io::Result<u32> {
+    let output = Command::new(java_home.join("bin").join("java"))
+        .arg("-version")
+        .output()?;
+    // `java -version` prints to stderr on all JDK versions.
+    let text = String::from_utf8_lossy(&output.stderr);
+    parse_java_version_output(&text).ok_or_else(|| std::io::Error::other(
+        format!("Failed to parse the output of `java -version`: {:?}", text)
+    ))
+}
+
+/// Parses the major version from the output of `javac -version`,
+/// e.g., `javac 17.0.13` or `javac 1.8.0_202`.
+///
+/// The version is the first token on the last meaningful line that starts with a digit,
+/// such that CRLF line endings and labels other than `javac` are tolerated.
+fn parse_javac_version_output(output: &str) -> Option<u32> {
+    let version = last_meaningful_line(output)
+        .split_whitespace()
+        .map(|token| token.trim_matches('"'))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))?;
+    parse_major_version(version)
+}
+
+/// Parses the major version from the output of `java -version`,
+/// e.g., `openjdk version "17.0.13" 2024-10-15` or `java version "1.8.0_202"`.
+///
+/// Unlike `javac`, the version is on the *first* meaningful line,
+/// as it is followed by lines describing the runtime and VM.
+fn parse_java_version_output(output: &str) -> Option<u32> {
+    let version = meaningful_lines(output).next()?
+        .split_whitespace()
+        .map(|token| token.trim_matches('"'))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))?;
+    parse_major_version(version)
+}
+
+/// Parses the major version from a full Java version string,
+/// e.g., `17` from `17.0.13`, or `8` from the legacy `1.8.0_202`.
+pub(crate) fn parse_major_version(version: &str) -> Option<u32> {
+    let mut components = version.split(['.', '_', '-', '+']);
+    match components.next()?.parse::<u32>().ok()? {
+        // Java 8 and earlier use the legacy `1.x` versioning scheme.
+        1 => components.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+/// Returns the lines of a Java tool's output, excluding blank lines
+/// and the `Picked up JAVA_TOOL_OPTIONS: ...` banner (and similar) that the JVM
+/// prints when certain environment variables are set.
+fn meaningful_lines(output: &str) -> impl Iterator<Item = &str> {
+    output.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("Picked up "))
+}
+
+/// Returns the last line of a Java tool's output that isn't blank
+/// or a `Picked up ...` banner, or an empty string if there is no such line.
+fn last_meaningful_line(output: &str) -> &str {
+    meaningful_lines(output).last().unwrap_or_default()
 }
 
 /// Returns the source version for compilation
 /// from `JAVA_SOURCE_VERSION`,
 pub fn java_source_version() -> Option<String> {
-    env::var(JAVA_SOURCE_VERSION).ok()
+    env_var(JAVA_SOURCE_VERSION).ok()
 }
 
 /// Returns the target version for compilation
 /// from `JAVA_TARGET_VERSION`,
 pub fn java_target_version() -> Option<String> {
-    env::var(JAVA_TARGET_VERSION).ok()
+    env_var(JAVA_TARGET_VERSION).ok()
+}
+
+/// Returns the JVM options specified by the `JAVA_OPTS` environment variable,
+/// split into separate arguments using shell-like quoting rules.
+///
+/// Returns an empty list if `JAVA_OPTS` is not set.
+pub fn java_opts_env() -> Vec<String> {
+    env_var(JAVA_OPTS).ok()
+        .map(|opts| crate::process::split_shell_words(&opts))
+        .unwrap_or_default()
+}
+
+/// Returns the `javac` options specified by the `ANDROID_JAVAC_OPTS` environment variable,
+/// split into separate arguments using shell-like quoting rules.
+///
+/// Returns an empty list if `ANDROID_JAVAC_OPTS` is not set.
+pub fn javac_opts_env() -> Vec<String> {
+    env_var(ANDROID_JAVAC_OPTS).ok()
+        .map(|opts| crate::process::split_shell_words(&opts))
+        .unwrap_or_default()
+}
+
+/// Returns the list of class paths specified by the `CLASSPATH` environment variable,
+/// split using the current platform's path separator.
+///
+/// Returns an empty list if `CLASSPATH` is not set.
+pub fn classpath_env() -> Vec<OsString> {
+    env_var(CLASSPATH).ok()
+        .map(|cp| env::split_paths(&cp)
+            .map(PathBuf::into_os_string)
+            .filter(|p| !p.is_empty())
+            .collect()
+        )
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a new, empty directory for the test with the given `name`.
+    fn test_dir(name: &str) -> crate::TestDir {
+        crate::TestDir::new(name)
+    }
+
+    #[test]
+    fn platform_dir_prefers_extension_platform() {
+        let platforms = test_dir("platform-ext");
+        std::fs::create_dir(platforms.join("android-33")).unwrap();
+        std::fs::create_dir(platforms.join("android-33-ext4")).unwrap();
+
+        assert_eq!(platform_dir(&platforms, "android-33-ext4"), Some(platforms.join("android-33-ext4")));
+        assert_eq!(platform_dir(&platforms, "android-33"), Some(platforms.join("android-33")));
+    }
+
+    #[test]
+    fn platform_dir_falls_back_to_base_platform() {
+        let platforms = test_dir("platform-base");
+        std::fs::create_dir(platforms.join("android-33")).unwrap();
+
+        assert_eq!(platform_dir(&platforms, "android-33-ext5"), Some(platforms.join("android-33")));
+        assert_eq!(platform_dir(&platforms, "android-34"), None);
+    }
+
+    #[test]
+    fn android_java_home_takes_precedence_over_java_home() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let android_jdk = test_dir("android-java-home");
+        let jdk = test_dir("java-home");
+        env::set_var(ANDROID_JAVA_HOME, &android_jdk);
+        env::set_var(JAVA_HOME, &jdk);
+        let both = java_home_from_env();
+        env::remove_var(ANDROID_JAVA_HOME);
+        let only_java_home = java_home_from_env();
+        env::set_var(ANDROID_JAVA_HOME, android_jdk.join("missing"));
+        let missing_android_jdk = java_home_from_env();
+        env::remove_var(ANDROID_JAVA_HOME);
+        env::remove_var(JAVA_HOME);
+
+        assert_eq!(both, Some(android_jdk.to_path_buf()));
+        assert_eq!(only_java_home, Some(jdk.to_path_buf()));
+        assert_eq!(missing_android_jdk, Some(jdk.to_path_buf()));
+    }
+
+    #[test]
+    fn parses_avd_list_skipping_log_lines() {
+        let output = "INFO    | Storing crashdata in: /tmp/android/emu-crash.db\nPixel_7_API_34\n\nTablet_API_33\n";
+        assert_eq!(parse_avd_list(output), ["Pixel_7_API_34", "Tablet_API_33"]);
+        assert!(parse_avd_list("").is_empty());
+    }
+
+    #[test]
+    fn parses_installed_packages_table() {
+        let output = "\
+Installed packages:
+  Path                 | Version | Description                | Location
+  -------              | ------- | -------                    | -------
+  build-tools;34.0.0   | 34.0.0  | Android SDK Build-Tools 34 | build-tools/34.0.0
+  platforms;android-34 | 3       | Android SDK Platform 34    | platforms/android-34
+";
+        assert_eq!(parse_installed_packages(output), ["build-tools;34.0.0", "platforms;android-34"]);
+    }
+
+    #[test]
+    fn normalizes_sdk_extension_forms() {
+        for raw in ["4", "ext4", "-ext4", " ext4 "] {
+            assert_eq!(normalize_sdk_extension(raw), Some(4), "{raw:?}");
+        }
+        for raw in ["extra", "", "ext", "extext4", "--4", "+4", "-ext-4", "ext 4"] {
+            assert_eq!(normalize_sdk_extension(raw), None, "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn parses_platform_versions() {
+        assert_eq!(platform_version("android-34"), Some((34, 0)));
+        assert_eq!(platform_version("android-33-ext4"), Some((33, 4)));
+        assert_eq!(platform_version("android-UpsideDownCake"), None);
+        assert_eq!(platform_version("34"), None);
+        assert!(platform_version("android-34") > platform_version("android-33-ext4"));
+    }
+
+    #[test]
+    fn parses_java_version_output_with_banners() {
+        let output = "Picked up JAVA_TOOL_OPTIONS: -Dfile.encoding=UTF8\n\
+            openjdk version \"17.0.13\" 2024-10-15\n\
+            OpenJDK Runtime Environment (build 17.0.13+11)\n";
+        assert_eq!(parse_java_version_output(output), Some(17));
+        assert_eq!(parse_java_version_output("java version \"1.8.0_202\""), Some(8));
+        assert_eq!(parse_java_version_output("Picked up _JAVA_OPTIONS: -Xmx1g\n"), None);
+    }
+
+    #[test]
+    fn parses_javac_version_output() {
+        assert_eq!(parse_javac_version_output("javac 17.0.13"), Some(17));
+        assert_eq!(parse_javac_version_output("javac 1.8.0_202\n"), Some(8));
+        assert_eq!(parse_javac_version_output("javac 21-ea"), Some(21));
+        assert_eq!(
+            parse_javac_version_output("Picked up JAVA_TOOL_OPTIONS: -Xss4m\njavac 11.0.2\n\n"),
+            Some(11),
+        );
+        assert_eq!(parse_javac_version_output("javac"), None);
+        assert_eq!(parse_javac_version_output(""), None);
+    }
+
+    #[test]
+    fn normalizes_env_paths() {
+        assert_eq!(normalize_env_path("  /opt/sdk/ \n".into()), "/opt/sdk");
+        assert_eq!(normalize_env_path("\"C:\\Android\\Sdk\\\"".into()), "C:\\Android\\Sdk");
+        assert_eq!(normalize_env_path("'/opt/my sdk'".into()), "/opt/my sdk");
+        assert_eq!(normalize_env_path("/".into()), "/");
+        assert_eq!(normalize_env_path("C:\\".into()), "C:\\");
+        assert_eq!(normalize_env_path("   ".into()), "");
+    }
+
+    #[test]
+    fn finds_latest_version_with_prefix() {
+        let build_tools = test_dir("build-tools-prefix");
+        for version in ["3.0.0", "33.0.1", "34.0.0-rc1", "34.0.0", "34.0.1"] {
+            std::fs::create_dir(build_tools.join(version)).unwrap();
+        }
+        assert_eq!(find_latest_version_with_prefix(&build_tools, "34"), Some(build_tools.join("34.0.1")));
+        assert_eq!(find_latest_version_with_prefix(&build_tools, "33.0"), Some(build_tools.join("33.0.1")));
+        assert_eq!(find_latest_version_with_prefix(&build_tools, "3"), Some(build_tools.join("3.0.0")));
+        assert_eq!(find_latest_version_with_prefix(&build_tools, "35"), None);
+        assert_eq!(find_latest_version(&build_tools), Some(build_tools.join("34.0.1")));
+    }
+
+    #[test]
+    fn parses_pkg_revision() {
+        let properties = "#Fri Mar 01 12:00:00 UTC 2024\nPkg.Desc=Android SDK Build-Tools 34\nPkg.Revision=34.0.0\n";
+        assert_eq!(parse_pkg_revision(properties).as_deref(), Some("34.0.0"));
+        assert_eq!(parse_pkg_revision("Pkg.Revision = 34.0.0 rc1\n").as_deref(), Some("34.0.0-rc1"));
+        assert_eq!(parse_pkg_revision("#Pkg.Revision=1.0.0\nPkg.Desc=x\n"), None);
+        assert_eq!(parse_pkg_revision("Pkg.Revision=\n"), None);
+    }
+
+    #[test]
+    fn missing_source_properties_is_not_found() {
+        let dir = test_dir("no-source-properties");
+        let err = check_build_tools_version(&dir).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn empty_build_tools_version_selects_latest() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = test_dir("sdk-build-tools");
+        for version in ["33.0.1", "34.0.0"] {
+            std::fs::create_dir_all(sdk.join("build-tools").join(version)).unwrap();
+        }
+        env::set_var(ANDROID_HOME, &sdk);
+        env::set_var(ANDROID_BUILD_TOOLS_VERSION, " ");
+        let empty = build_tools_dir(None);
+        env::set_var(ANDROID_BUILD_TOOLS_VERSION, "33.0.1");
+        let explicit = build_tools_dir(None);
+        let empty_arg = build_tools_dir(Some(""));
+        env::remove_var(ANDROID_BUILD_TOOLS_VERSION);
+        env::remove_var(ANDROID_HOME);
+
+        assert_eq!(empty, Some(sdk.join("build-tools").join("34.0.0")));
+        assert_eq!(explicit, Some(sdk.join("build-tools").join("33.0.1")));
+        assert_eq!(empty_arg, Some(sdk.join("build-tools").join("33.0.1")));
+    }
+
+    #[test]
+    fn compile_sdk_aliases_have_lowest_precedence() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        env::set_var(COMPILE_SDK_VERSION, "33");
+        let compile_sdk_version = env_android_platform_api_level();
+        env::set_var(ANDROID_COMPILE_SDK, "34");
+        let android_compile_sdk = env_android_platform_api_level();
+        env::set_var(ANDROID_PLATFORM, "android-35");
+        let android_platform = env_android_platform_api_level();
+        env::remove_var(ANDROID_PLATFORM);
+        env::remove_var(ANDROID_COMPILE_SDK);
+        env::remove_var(COMPILE_SDK_VERSION);
+
+        assert_eq!(compile_sdk_version.as_deref(), Some("android-33"));
+        assert_eq!(android_compile_sdk.as_deref(), Some("android-34"));
+        assert_eq!(android_platform.as_deref(), Some("android-35"));
+    }
+
+    #[test]
+    fn android_jar_for_api_uses_extension_platform() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = test_dir("sdk-jar-for-api");
+        for platform in ["android-33", "android-33-ext4"] {
+            std::fs::create_dir_all(sdk.join("platforms").join(platform)).unwrap();
+            std::fs::write(sdk.join("platforms").join(platform).join("android.jar"), b"").unwrap();
+        }
+        env::set_var(ANDROID_HOME, &sdk);
+        let base = android_jar_for_api(33, None);
+        let ext = android_jar_for_api(33, Some(4));
+        let missing_ext = android_jar_for_api(33, Some(5));
+        env::set_var(ANDROID_SDK_EXTENSION, "ext4");
+        let env_ext = android_jar_for_api(33, None);
+        env::remove_var(ANDROID_SDK_EXTENSION);
+        env::remove_var(ANDROID_HOME);
+
+        let jar = |platform: &str| Some(sdk.join("platforms").join(platform).join("android.jar"));
+        assert_eq!(base, jar("android-33"));
+        assert_eq!(ext, jar("android-33-ext4"));
+        assert_eq!(missing_ext, jar("android-33"));
+        assert_eq!(env_ext, jar("android-33-ext4"));
+    }
+
+    #[test]
+    fn strict_mode_disables_latest_version_fallback() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = test_dir("sdk-strict");
+        std::fs::create_dir_all(sdk.join("platforms").join("android-34")).unwrap();
+        std::fs::create_dir_all(sdk.join("build-tools").join("34.0.0")).unwrap();
+        env::set_var(ANDROID_HOME, &sdk);
+        let lenient = (resolved_platform(), build_tools_dir(None));
+        env::set_var(ANDROID_STRICT, "true");
+        let strict = (resolved_platform(), build_tools_dir(None));
+        let strict_explicit = build_tools_dir(Some("34.0.0"));
+        env::set_var(ANDROID_STRICT, "0");
+        let disabled = is_strict_mode();
+        env::remove_var(ANDROID_STRICT);
+        env::remove_var(ANDROID_HOME);
+
+        assert_eq!(lenient, (Some("android-34".into()), Some(sdk.join("build-tools").join("34.0.0"))));
+        assert_eq!(strict, (None, None));
+        assert_eq!(strict_explicit, Some(sdk.join("build-tools").join("34.0.0")));
+        assert!(!disabled);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn ndk_toolchain_clang_maps_rust_targets() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = test_dir("sdk-ndk");
+        let bin = sdk.join("ndk").join("26.1.10909125")
+            .join("toolchains").join("llvm").join("prebuilt").join("linux-x86_64").join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::create_dir_all(sdk.join("ndk").join("25.2.9519653")).unwrap();
+        for clang in ["aarch64-linux-android21-clang", "armv7a-linux-androideabi21-clang"] {
+            std::fs::write(bin.join(clang), b"").unwrap();
+        }
+        env::remove_var(ANDROID_NDK_HOME);
+        env::remove_var(ANDROID_NDK_ROOT);
+        env::set_var(ANDROID_HOME, &sdk);
+        let aarch64 = ndk_toolchain_clang("aarch64-linux-android", 21);
+        let armv7 = ndk_toolchain_clang("armv7-linux-androideabi", 21);
+        let missing_api = ndk_toolchain_clang("aarch64-linux-android", 34);
+        env::remove_var(ANDROID_HOME);
+
+        assert_eq!(aarch64, Some(bin.join("aarch64-linux-android21-clang")));
+        assert_eq!(armv7, Some(bin.join("armv7a-linux-androideabi21-clang")));
+        assert_eq!(missing_api, None);
+    }
+
+    #[test]
+    fn jre_within_jdk_resolves_to_jdk() {
+        let jdk = test_dir("jdk-with-jre");
+        std::fs::create_dir_all(jdk.join("bin")).unwrap();
+        std::fs::create_dir_all(jdk.join("jre").join("bin")).unwrap();
+        std::fs::write(jdk.join("bin").join(JAVAC_EXE), b"").unwrap();
+        let jre = test_dir("jre-only");
+
+        assert_eq!(jdk_dir_with_javac(&jdk.join("jre")), Some(jdk.to_path_buf()));
+        assert_eq!(jdk_dir_with_javac(&jdk), Some(jdk.to_path_buf()));
+        assert_eq!(jdk_dir_with_javac(&jre), None);
+    }
+
+    #[test]
+    fn java_home_preferring_falls_back_to_java_home() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let jdk = test_dir("preferred-fallback");
+        env::remove_var(ANDROID_JAVA_HOME);
+        env::set_var(JAVA_HOME, &jdk);
+        let preferred = java_home_preferring(&[999]);
+        env::remove_var(JAVA_HOME);
+
+        assert_eq!(preferred, Some(jdk.to_path_buf()));
+    }
+
+    #[test]
+    fn cmdline_tools_prefers_latest_dir() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = test_dir("sdk-cmdline-tools");
+        let cmdline_tools = sdk.join("cmdline-tools");
+        for version in ["9.0", "11.0"] {
+            std::fs::create_dir_all(cmdline_tools.join(version).join("bin")).unwrap();
+        }
+        env::set_var(ANDROID_HOME, &sdk);
+        let versioned = cmdline_tools_dir();
+        std::fs::create_dir_all(cmdline_tools.join("latest").join("bin")).unwrap();
+        let latest = cmdline_tools_dir();
+        let platform_tools = platform_tools_dir();
+        env::remove_var(ANDROID_HOME);
+
+        assert_eq!(versioned, Some(cmdline_tools.join("11.0").join("bin")));
+        assert_eq!(latest, Some(cmdline_tools.join("latest").join("bin")));
+        assert_eq!(platform_tools, None);
+    }
+
+    #[test]
+    fn tool_env_vars_override_build_tools() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = test_dir("sdk-tool-override");
+        let build_tools = sdk.join("build-tools").join("34.0.0");
+        std::fs::create_dir_all(&build_tools).unwrap();
+        let aapt2_exe = format!("aapt2{}", env::consts::EXE_SUFFIX);
+        std::fs::write(build_tools.join(&aapt2_exe), b"").unwrap();
+        std::fs::write(sdk.join("custom-aapt2"), b"").unwrap();
+        env::set_var(ANDROID_HOME, &sdk);
+        let discovered = aapt2(None);
+        env::set_var(ANDROID_AAPT2, sdk.join("custom-aapt2"));
+        let overridden = aapt2(None);
+        env::set_var(ANDROID_AAPT2, sdk.join("missing-aapt2"));
+        let missing_override = aapt2(None);
+        env::remove_var(ANDROID_AAPT2);
+        env::remove_var(ANDROID_HOME);
+
+        assert_eq!(discovered, Some(build_tools.join(&aapt2_exe)));
+        assert_eq!(overridden, Some(sdk.join("custom-aapt2")));
+        assert_eq!(missing_override, Some(build_tools.join(&aapt2_exe)));
+    }
+
+    #[test]
+    #[cfg(feature = "cargo")]
+    fn cargo_target_api_level_from_ndk_env() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        env::set_var("RUSTC_LINKER", "/ndk/bin/aarch64-linux-android24-clang");
+        let not_android = cargo_target_api_level();
+        env::set_var("CARGO_CFG_TARGET_OS", "android");
+        let from_linker = cargo_target_api_level();
+        env::set_var("CARGO_NDK_ANDROID_PLATFORM", "android-26");
+        let from_cargo_ndk = cargo_target_api_level();
+        env::remove_var("CARGO_NDK_ANDROID_PLATFORM");
+        env::remove_var("CARGO_CFG_TARGET_OS");
+        env::remove_var("RUSTC_LINKER");
+
+        assert_eq!(not_android, None);
+        assert_eq!(from_linker, Some(24));
+        assert_eq!(from_cargo_ndk, Some(26));
+    }
+
+    #[test]
+    fn resolved_platform_and_build_tools_names() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = test_dir("sdk-resolved-names");
+        for dir in ["platforms/android-33", "platforms/android-33-ext4", "platforms/android-34", "build-tools/34.0.0"] {
+            std::fs::create_dir_all(sdk.join(dir)).unwrap();
+        }
+        env::set_var(ANDROID_HOME, &sdk);
+        let latest = resolved_platform();
+        env::set_var(ANDROID_PLATFORM, "33");
+        env::set_var(ANDROID_SDK_EXTENSION, "4");
+        let from_env = resolved_platform();
+        let build_tools = resolved_build_tools();
+        env::remove_var(ANDROID_SDK_EXTENSION);
+        env::remove_var(ANDROID_PLATFORM);
+        env::remove_var(ANDROID_HOME);
+
+        assert_eq!(latest.as_deref(), Some("android-34"));
+        assert_eq!(from_env.as_deref(), Some("android-33-ext4"));
+        assert_eq!(build_tools.as_deref(), Some("34.0.0"));
+    }
+
+    #[test]
+    fn sdk_and_jdk_availability_from_env() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = test_dir("sdk-available");
+        let jdk = test_dir("jdk-available");
+        std::fs::create_dir_all(jdk.join("bin")).unwrap();
+        std::fs::write(jdk.join("bin").join(JAVAC_EXE), b"").unwrap();
+        env::set_var(ANDROID_HOME, &sdk);
+        env::remove_var(ANDROID_JAVA_HOME);
+        env::set_var(JAVA_HOME, &jdk);
+        let available = (is_sdk_available(), is_jdk_available());
+        env::remove_var(JAVA_HOME);
+        env::remove_var(ANDROID_HOME);
+
+        assert_eq!(available, (true, true));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn javac_version_on_stderr_with_banner_on_stdout() {
+        use std::os::unix::fs::PermissionsExt;
+        let jdk = test_dir("jdk-stderr-version");
+        std::fs::create_dir_all(jdk.join("bin")).unwrap();
+        let javac = jdk.join("bin").join("javac");
+        std::fs::write(&javac, "#!/bin/sh\necho 'Picked up JAVA_TOOL_OPTIONS: -Xss4m'\necho 'javac 1.8.0_202' >&2\n").unwrap();
+        std::fs::set_permissions(&javac, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(check_javac_version(&jdk).unwrap(), 8);
+    }
+
+    #[test]
+    fn resolution_sources() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = test_dir("sdk-resolution-sources");
+        for platform in ["android-33", "android-34"] {
+            std::fs::create_dir_all(sdk.join("platforms").join(platform)).unwrap();
+            std::fs::write(sdk.join("platforms").join(platform).join("android.jar"), b"").unwrap();
+        }
+        std::fs::create_dir_all(sdk.join("build-tools").join("34.0.0")).unwrap();
+        env::set_var(ANDROID_HOME, &sdk);
+        let source = |resolved: Option<(PathBuf, ResolutionSource)>| resolved.map(|(_, source)| source);
+        let latest = source(android_jar_resolved(None));
+        let explicit = source(android_jar_resolved(Some("android-33")));
+        env::set_var(ANDROID_API_LEVEL, "33");
+        let env_platform = source(android_jar_resolved(None));
+        env::set_var(ANDROID_JAR, sdk.join("platforms").join("android-34").join("android.jar"));
+        let env_jar = source(android_jar_resolved(Some("android-33")));
+        env::set_var(ANDROID_BUILD_TOOLS_VERSION, "34");
+        let env_build_tools = source(build_tools_dir_resolved(None));
+        env::remove_var(ANDROID_BUILD_TOOLS_VERSION);
+        let latest_build_tools = source(build_tools_dir_resolved(None));
+        env::remove_var(ANDROID_JAR);
+        env::remove_var(ANDROID_API_LEVEL);
+        env::remove_var(ANDROID_HOME);
+
+        assert_eq!(latest, Some(ResolutionSource::LatestFallback));
+        assert_eq!(explicit, Some(ResolutionSource::ExplicitArg));
+        assert_eq!(env_platform, Some(ResolutionSource::EnvPlatform));
+        assert_eq!(env_jar, Some(ResolutionSource::EnvVar));
+        assert_eq!(env_build_tools, Some(ResolutionSource::EnvBuildTools));
+        assert_eq!(latest_build_tools, Some(ResolutionSource::LatestFallback));
+    }
 }
@@ -0,0 +1,27 @@
+//! Describing how a toolchain path was discovered.
+
+/// Where a path returned by one of the `*_resolved()` functions came from,
+/// e.g., [`android_jar_resolved()`](crate::android_jar_resolved).
+///
+/// This allows callers to decide whether to trust a path,
+/// e.g., by rejecting one that was only guessed via [`ResolutionSource::LatestFallback`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResolutionSource {
+    /// The path was given directly by an environment variable, e.g., `ANDROID_JAR`.
+    EnvVar,
+    /// The path was found using a platform or version that was explicitly passed
+    /// as an argument to the function.
+    ExplicitArg,
+    /// The path was found using a platform specified by an environment variable,
+    /// e.g., `ANDROID_PLATFORM` or `ANDROID_API_LEVEL`.
+    EnvPlatform,
+    /// The path was found using the build tools version specified by
+    /// the `ANDROID_BUILD_TOOLS_VERSION` environment variable.
+    EnvBuildTools,
+    /// The path was found using the API level of the Android target being built by Cargo.
+    /// This is only possible if the `cargo` feature is enabled.
+    CargoTarget,
+    /// Nothing was specified, so the path was found by choosing
+    /// the latest version installed in the Android SDK.
+    LatestFallback,
+}
@@ -0,0 +1,76 @@
+//! A snapshot of all toolchain paths discovered by this crate.
+
+use std::path::PathBuf;
+
+/// The results of discovering the Android SDK and Java toolchain paths,
+/// which can be forwarded to later build stages or dependent crates.
+///
+/// Each field is `None` if that path could not be found.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResolvedPaths {
+    /// The Android SDK directory, from [`android_sdk()`](super::android_sdk).
+    pub android_sdk: Option<PathBuf>,
+    /// The `android.jar` file, from [`android_jar()`](super::android_jar).
+    pub android_jar: Option<PathBuf>,
+    /// The `d8.jar` file, from [`android_d8_jar()`](super::android_d8_jar).
+    pub d8_jar: Option<PathBuf>,
+    /// The Java home directory, from [`java_home()`](super::java_home).
+    pub java_home: Option<PathBuf>,
+}
+
+impl ResolvedPaths {
+    /// Discovers all toolchain paths using the default resolution logic
+    /// and the environment variables documented at the crate level.
+    pub fn resolve() -> Self {
+        Self {
+            android_sdk: super::android_sdk(),
+            android_jar: super::android_jar(None),
+            d8_jar: super::android_d8_jar(None),
+            java_home: super::java_home(),
+        }
+    }
+
+    /// Returns a `(key, path)` pair for each path that was found,
+    /// where the key is the environment variable conventionally used for that path.
+    pub fn env_vars(&self) -> Vec<(&'static str, &PathBuf)> {
+        [
+            (super::ANDROID_HOME,   self.android_sdk.as_ref()),
+            (super::ANDROID_JAR,    self.android_jar.as_ref()),
+            (super::ANDROID_D8_JAR, self.d8_jar.as_ref()),
+            (super::JAVA_HOME,      self.java_home.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(key, path)| Some((key, path?)))
+        .collect()
+    }
+
+    /// Prints a `cargo:rustc-env=KEY=PATH` line for each path that was found,
+    /// which makes it available to the crate being built via `env!("KEY")`.
+    ///
+    /// The keys are the same as those returned by [`ResolvedPaths::env_vars()`].
+    #[cfg(feature = "cargo")]
+    pub fn emit_rustc_env(&self) {
+        for (key, path) in self.env_vars() {
+            println!("cargo:rustc-env={}={}", key, path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_vars_skip_missing_paths() {
+        let paths = ResolvedPaths {
+            android_sdk: Some(PathBuf::from("/sdk")),
+            java_home: Some(PathBuf::from("/jdk")),
+            ..Default::default()
+        };
+        assert_eq!(paths.env_vars(), [
+            (crate::env_paths::ANDROID_HOME, &PathBuf::from("/sdk")),
+            (crate::env_paths::JAVA_HOME, &PathBuf::from("/jdk")),
+        ]);
+        assert!(ResolvedPaths::default().env_vars().is_empty());
+    }
+}
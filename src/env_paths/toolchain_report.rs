@@ -0,0 +1,93 @@
+//! A human-readable summary of toolchain discovery, for troubleshooting.
+
+use std::fmt::Write;
+use std::path::Path;
+use super::*;
+
+/// The environment variables that influence toolchain discovery.
+const CONSULTED_ENV_VARS: &[&str] = &[
+    ANDROID_HOME,
+    ANDROID_SDK_ROOT,
+    ANDROID_JAR,
+    ANDROID_PLATFORM,
+    ANDROID_API_LEVEL,
+    ANDROID_SDK_VERSION,
+    ANDROID_COMPILE_SDK,
+    COMPILE_SDK_VERSION,
+    ANDROID_SDK_EXTENSION,
+    ANDROID_BUILD_TOOLS_VERSION,
+    ANDROID_D8_JAR,
+    ANDROID_STRICT,
+    ANDROID_NDK_HOME,
+    ANDROID_NDK_ROOT,
+    ANDROID_JAVA_HOME,
+    JAVA_HOME,
+    JAVA_SOURCE_VERSION,
+    JAVA_TARGET_VERSION,
+    CLASSPATH,
+];
+
+/// Returns a human-readable, multi-line summary of every toolchain path
+/// discovered by this crate, along with the environment variables that were consulted.
+///
+/// Each entry shows either the discovered value (and where it came from, if applicable)
+/// or `not found`. This is intended for inclusion in bug reports, e.g.,
+/// by printing it from a build script when a build step fails.
+pub fn toolchain_report() -> String {
+    fn path_entry(path: Option<&Path>) -> String {
+        path.map_or_else(|| "not found".into(), |p| p.display().to_string())
+    }
+    fn resolved_entry(resolved: Option<(PathBuf, ResolutionSource)>) -> String {
+        resolved.map_or_else(
+            || "not found".into(),
+            |(path, source)| format!("{} ({source:?})", path.display()),
+        )
+    }
+
+    let java_home = java_home();
+    let javac_version = java_home_with_javac()
+        .and_then(|jh| check_javac_version(&jh).ok())
+        .map_or_else(|| "not found".into(), |v| v.to_string());
+
+    let mut report = String::from("Toolchain:\n");
+    for (label, value) in [
+        ("Android SDK",   path_entry(android_sdk().as_deref())),
+        ("Platform",      resolved_platform().unwrap_or_else(|| "not found".into())),
+        ("android.jar",   resolved_entry(android_jar_resolved(None))),
+        ("Build tools",   resolved_entry(build_tools_dir_resolved(None))),
+        ("d8.jar",        resolved_entry(android_d8_jar_resolved(None))),
+        ("Android NDK",   path_entry(android_ndk().as_deref())),
+        ("Java home",     path_entry(java_home.as_deref())),
+        ("javac version", javac_version),
+    ] {
+        let _ = writeln!(report, "  {label:<14} {value}");
+    }
+
+    report.push_str("Environment variables:\n");
+    for key in CONSULTED_ENV_VARS {
+        let value = env::var_os(key)
+            .map_or_else(|| "(not set)".into(), |v| format!("{v:?}"));
+        let _ = writeln!(report, "  {key:<28} {value}");
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lists_paths_and_env_vars() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = crate::TestDir::new("sdk-report");
+        env::set_var(ANDROID_HOME, &sdk);
+        env::remove_var(ANDROID_STRICT);
+        let report = toolchain_report();
+        env::remove_var(ANDROID_HOME);
+
+        assert!(report.contains(&format!("  Android SDK    {}\n", sdk.display())), "{report}");
+        assert!(report.contains("  Build tools    not found\n"), "{report}");
+        assert!(report.contains(&format!("  ANDROID_HOME                 {:?}\n", sdk.as_os_str())), "{report}");
+        assert!(report.contains("  ANDROID_STRICT               (not set)\n"), "{report}");
+    }
+}
@@ -0,0 +1,132 @@
+//! Helpers for walking directories of input and output files.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Recursively visits every file within the given directory,
+/// invoking the callback `cb` on each file's path.
+///
+/// Symbolic links to directories are followed.
+pub(crate) fn visit_dirs(dir: &Path, cb: &mut dyn FnMut(&Path)) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            visit_dirs(&path, cb)?;
+        } else {
+            cb(&path);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the paths of all files within the given directory (recursively)
+/// that have the given extension, e.g., `"java"`, `"class"`, or `"jar"`, sorted by path.
+///
+/// The extension may be given with or without a leading `.`,
+/// and is compared case-sensitively.
+pub fn collect_files_by_ext<P: AsRef<Path>>(dir: P, ext: &str) -> io::Result<Vec<PathBuf>> {
+    let ext = ext.strip_prefix('.').unwrap_or(ext);
+    let mut files = Vec::new();
+    visit_dirs(dir.as_ref(), &mut |path| {
+        if path.extension().is_some_and(|e| e == ext) {
+            files.push(path.to_path_buf());
+        }
+    })?;
+    files.sort();
+    Ok(files)
+}
+
+/// Splits the contents of a javac-style `@argfile` into its arguments.
+///
+/// Arguments are separated by whitespace, and may be quoted with `'` or `"`
+/// to include whitespace. Within quotes, a backslash escapes the next character
+/// (with `\n`, `\t`, `\r`, and `\f` denoting control characters), and a backslash
+/// at the end of a line continues the argument after the next line's leading whitespace.
+/// Outside of quotes, backslashes are kept as is, such that Windows paths remain intact.
+/// A `#` at the start of an argument comments out the rest of the line.
+pub(crate) fn parse_argfile(contents: &str) -> io::Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut chars = contents.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() { }
+        let Some(first) = chars.peek().copied() else { break };
+        if first == '#' {
+            while chars.next_if(|c| *c != '\n' && *c != '\r').is_some() { }
+            continue;
+        }
+
+        let mut arg = String::new();
+        let mut quote: Option<char> = None;
+        while let Some(c) = chars.next() {
+            match (quote, c) {
+                (None, _) if c.is_whitespace() => break,
+                (None, '"' | '\'') => quote = Some(c),
+                (Some(q), _) if c == q => quote = None,
+                (Some(_), '\\') => match chars.next() {
+                    Some('n') => arg.push('\n'),
+                    Some('t') => arg.push('\t'),
+                    Some('r') => arg.push('\r'),
+                    Some('f') => arg.push('\u{c}'),
+                    Some('\n' | '\r') => {
+                        while chars.next_if(|c| c.is_whitespace()).is_some() { }
+                    }
+                    Some(escaped) => arg.push(escaped),
+                    None => arg.push('\\'),
+                },
+                _ => arg.push(c),
+            }
+        }
+        if let Some(q) = quote {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unmatched quote {q:?} in argument file, after {arg:?}"),
+            ));
+        }
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quoted_argfile() {
+        let contents = "\
+# Generated by another build system
+src/A.java \"src/my dir/B.java\"
+'src/C D.java'\tC:\\src\\E.java
+\"src/lo\\
+    ng.java\" \"tab\\there\"
+";
+        assert_eq!(parse_argfile(contents).unwrap(), [
+            "src/A.java",
+            "src/my dir/B.java",
+            "src/C D.java",
+            "C:\\src\\E.java",
+            "src/long.java",
+            "tab\there",
+        ]);
+    }
+
+    #[test]
+    fn unmatched_quote_is_an_error() {
+        let err = parse_argfile("src/A.java \"src/B.java\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(parse_argfile("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn collects_files_by_ext() {
+        let dir = crate::TestDir::new("libs");
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        for file in ["b.jar", "nested/a.jar", "c.JAR", "d.jar.txt"] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+        let expected = [dir.join("b.jar"), dir.join("nested/a.jar")];
+        assert_eq!(collect_files_by_ext(&dir, "jar").unwrap(), expected);
+        assert_eq!(collect_files_by_ext(&dir, ".jar").unwrap(), expected);
+        assert!(collect_files_by_ext(dir.join("missing"), "jar").is_err());
+    }
+}
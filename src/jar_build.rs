@@ -0,0 +1,224 @@
+//! Builder for customizing and invoking a `jar` command.
+
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus};
+use crate::env_paths::{self, PathExt};
+
+/// A builder for a `jar` command that can be invoked
+/// to package class files and resources into a JAR file.
+///
+/// If you need to customize the `jar` command beyond what is provided here,
+/// you can use the [`JarBuild::command()`] method to get a [`Command`]
+/// that can be further customized with additional arguments.
+///
+/// Documentation on `jar` options are based on
+/// <https://dev.java/learn/jvm/tools/core/jar/>.
+#[derive(Clone, Debug, Default)]
+pub struct JarBuild {
+    /// Override the default `JAVA_HOME` path.
+    /// Otherwise, the default path is found using the `JAVA_HOME` env var.
+    java_home: Option<PathBuf>,
+
+    /// Whether to create a new JAR file or update an existing one.
+    mode: JarMode,
+
+    /// The JAR file to be created or updated.
+    jar_file: Option<OsString>,
+
+    /// A manifest file whose contents should be included in the JAR's manifest.
+    manifest: Option<OsString>,
+
+    /// The entry point (main class) of a standalone application bundled in the JAR.
+    entry_point: Option<String>,
+
+    /// The files to be added to the JAR, each with an optional base directory
+    /// that the `jar` tool will change into before adding that file ("-C" flag).
+    files: Vec<(Option<OsString>, OsString)>,
+}
+
+/// The operating mode of a `jar` command.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JarMode {
+    /// Create a new JAR file, overwriting any existing file ("c" flag).
+    #[default]
+    Create,
+    /// Update an existing JAR file by adding files to it ("u" flag).
+    Update,
+}
+
+impl JarBuild {
+    /// Creates a new `JarBuild` instance with default values,
+    /// which can be further customized using the builder methods.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Executes the `jar` command based on this `JarBuild` instance.
+    pub fn run(&self) -> std::io::Result<ExitStatus> {
+        self.command()?.status()
+    }
+
+    /// Returns a [`Command`] based on this `JarBuild` instance
+    /// that can be inspected or customized before being executed.
+    ///
+    /// The options are emitted in the traditional bundled form
+    /// supported by all JDK versions, e.g., `jar cfme <jar> <manifest> <entry> <files>...`.
+    pub fn command(&self) -> std::io::Result<Command> {
+        let jh_clone = self.java_home.clone();
+        let java_home = jh_clone
+            .and_then(PathExt::path_if_exists)
+            .or_else(env_paths::java_home_with_javac)
+            .ok_or_else(|| std::io::Error::other(
+                "JAVA_HOME not provided, and no JDK containing `javac` could be auto-discovered."
+            ))?;
+
+        let jar_file = self.jar_file.as_ref().ok_or_else(|| std::io::Error::other(
+            "No JAR file was specified."
+        ))?;
+
+        let mut cmd = Command::new(java_home.join("bin").join("jar"));
+
+        // The order of the flag letters must match the order of their arguments.
+        let mut flags = String::from(match self.mode {
+            JarMode::Create => "c",
+            JarMode::Update => "u",
+        });
+        let mut flag_args: Vec<&OsStr> = Vec::new();
+        flags.push('f');
+        flag_args.push(jar_file);
+        if let Some(manifest) = self.manifest.as_ref() {
+            flags.push('m');
+            flag_args.push(manifest);
+        }
+        if let Some(entry_point) = self.entry_point.as_ref() {
+            flags.push('e');
+            flag_args.push(OsStr::new(entry_point));
+        }
+        cmd.arg(flags).args(flag_args);
+
+        for (base_dir, file) in self.files.iter() {
+            if let Some(dir) = base_dir {
+                cmd.arg("-C").arg(dir);
+            }
+            cmd.arg(file);
+        }
+
+        Ok(cmd)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    //////////////////////// Builder methods below ////////////////////////////
+    ///////////////////////////////////////////////////////////////////////////
+
+    /// Override the default `JAVA_HOME` path.
+    ///
+    /// If not set, the default path is found using the `JAVA_HOME` env var.
+    pub fn java_home<P: Into<PathBuf>>(&mut self, java_home: P) -> &mut Self {
+        self.java_home = Some(java_home.into());
+        self
+    }
+
+    /// Set whether to create a new JAR file or update an existing one.
+    ///
+    /// The default is [`JarMode::Create`].
+    pub fn mode(&mut self, mode: JarMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Specify the JAR file to be created or updated. This is required.
+    #[doc(alias("-f", "--file"))]
+    pub fn jar_file<P: AsRef<OsStr>>(&mut self, jar_file: P) -> &mut Self {
+        self.jar_file = Some(jar_file.as_ref().into());
+        self
+    }
+
+    /// Specify a manifest file whose contents will be included in the JAR's manifest.
+    #[doc(alias("-m", "--manifest"))]
+    pub fn manifest<P: AsRef<OsStr>>(&mut self, manifest: P) -> &mut Self {
+        self.manifest = Some(manifest.as_ref().into());
+        self
+    }
+
+    /// Specify the entry point (main class) for a standalone application
+    /// bundled into the JAR, e.g., `com.example.Main`.
+    #[doc(alias("-e", "--main-class", "main_class"))]
+    pub fn entry_point<S: Into<String>>(&mut self, entry_point: S) -> &mut Self {
+        self.entry_point = Some(entry_point.into());
+        self
+    }
+
+    /// Adds the entire contents of the given directory to the JAR,
+    /// with paths inside the JAR being relative to that directory.
+    ///
+    /// This is equivalent to `-C <base_dir> .`, which is typically used
+    /// to package a `javac` classes output directory.
+    #[doc(alias("-C"))]
+    pub fn base_dir<P: AsRef<OsStr>>(&mut self, base_dir: P) -> &mut Self {
+        self.files.push((Some(base_dir.as_ref().into()), ".".into()));
+        self
+    }
+
+    /// Adds a file (or directory) to the JAR, given relative to the given `base_dir`.
+    ///
+    /// This is equivalent to `-C <base_dir> <file>`.
+    #[doc(alias("-C"))]
+    pub fn file_in_dir<D, P>(&mut self, base_dir: D, file: P) -> &mut Self
+    where
+        D: AsRef<OsStr>,
+        P: AsRef<OsStr>,
+    {
+        self.files.push((Some(base_dir.as_ref().into()), file.as_ref().into()));
+        self
+    }
+
+    /// Adds a file (or directory) to the JAR.
+    pub fn file<P: AsRef<OsStr>>(&mut self, file: P) -> &mut Self {
+        self.files.push((None, file.as_ref().into()));
+        self
+    }
+
+    /// Adds multiple files (or directories) to the JAR.
+    ///
+    /// This is the same as calling [`JarBuild::file()`] multiple times.
+    pub fn files<P>(&mut self, files: P) -> &mut Self
+    where
+        P: IntoIterator,
+        P::Item: AsRef<OsStr>,
+    {
+        self.files.extend(files.into_iter().map(|f| (None, f.as_ref().into())));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(jar: &JarBuild) -> Vec<OsString> {
+        jar.command().unwrap().get_args().map(|arg| arg.to_owned()).collect()
+    }
+
+    #[test]
+    fn bundled_flags_match_argument_order() {
+        let mut jar = JarBuild::new();
+        jar.java_home(std::env::temp_dir())
+            .entry_point("com.example.Main")
+            .manifest("MANIFEST.MF")
+            .jar_file("app.jar")
+            .base_dir("classes")
+            .file("README");
+        assert_eq!(args(&jar), ["cfme", "app.jar", "MANIFEST.MF", "com.example.Main", "-C", "classes", ".", "README"]);
+
+        jar.mode(JarMode::Update);
+        assert_eq!(args(&jar)[0], "ufme");
+    }
+
+    #[test]
+    fn jar_file_is_required() {
+        let mut jar = JarBuild::new();
+        jar.java_home(std::env::temp_dir()).file("A.class");
+        assert!(jar.command().is_err());
+    }
+}
@@ -1,9 +1,11 @@
 //! Builder for customizing and invoking a `javac` command.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::ffi::{OsStr, OsString};
 use std::process::{Command, ExitStatus};
 use crate::env_paths::{self, PathExt};
+use crate::{Diagnostic, ResolvedCommand};
 
 /// A builder for a `javac` command that can be invoked.
 ///
@@ -14,16 +16,22 @@ use crate::env_paths::{self, PathExt};
 /// Documentation on `javac` options are based on
 /// <https://dev.java/learn/jvm/tools/core/javac/>.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct JavaBuild {
     /// Override the default `JAVA_HOME` path.
     /// Otherwise, the default path is found using the `JAVA_HOME` env var.
-    java_home: Option<PathBuf>,
+    pub(crate) java_home: Option<PathBuf>,
     /// Debug info to include in the output ("-g" flag).
     debug_info: Option<DebugInfo>,
     /// If `true`, all warnings are disabled.
     nowarn: bool,
     /// Enable verbose output.
     verbose: bool,
+    /// Lint categories to enable ("-Xlint:<category>").
+    xlint_enabled: Vec<String>,
+    /// Lint categories to disable ("-Xlint:-<category>").
+    xlint_disabled: Vec<String>,
     /// If `true`, warnings are treated as compilation errors.
     warnings_as_errors: bool,
     /// If `true`, show full descriptions of all places where
@@ -32,24 +40,61 @@ pub struct JavaBuild {
     deprecation: bool,
     /// If `true`, enable preview language features.
     enable_preview_features: bool,
+    /// Whether to generate class files for implicitly referenced files ("-implicit" flag).
+    implicit: Option<Implicit>,
+    /// The Java version for source compatibility ("--source" flag).
+    /// If not provided, the `JAVA_SOURCE_VERSION` env var is used.
+    source_version: Option<String>,
+    /// The Java version for target compatibility ("--target" flag).
+    /// If not provided, the `JAVA_TARGET_VERSION` env var is used.
+    target_version: Option<String>,
+    /// The Java version to compile for ("--release" flag),
+    /// which supersedes both `source_version` and `target_version`.
+    #[doc(alias = "--release")]
+    release_version: Option<String>,
     /// Specify where to find user class files and annotation processors.
     /// If not provided, the current directory will be used.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     class_paths: Vec<OsString>,
+    /// If `true`, the entries in the `CLASSPATH` environment variable
+    /// are prepended to `class_paths`.
+    inherit_classpath_env: bool,
+    /// If `true`, the options in the `ANDROID_JAVAC_OPTS` environment variable
+    /// are passed to `javac`.
+    inherit_java_opts: bool,
     /// Specify where to find input source files.
     /// If not specified, `class_paths` will be searched for source files.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     source_paths: Vec<OsString>,
     /// Override the location of bootstrap class files.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     boot_class_paths: Vec<OsString>,
     /// Override the location of installed extensions.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     extension_dirs: Vec<OsString>,
     /// Override the location of endorsed standards path.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     endorsed_dirs: Vec<OsString>,
     /// Specify names of the annotation processors to run.
     /// Setting this will bypass the default discovery process.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     annotation_processors: Vec<OsString>,
     /// Specify where to find annotation processors.
     /// If not provided, the `class_paths` will be searched.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     annotation_processor_paths: Vec<OsString>,
+    /// Specify where to find modular annotation processors.
+    #[doc(alias = "--processor-module-path")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
+    annotation_processor_module_paths: Vec<OsString>,
+    /// Limit the universe of observable modules.
+    #[doc(alias = "--limit-modules")]
+    limit_modules: Vec<String>,
+    /// Override or augment modules with classes and resources,
+    /// with each entry in the form `module=path`.
+    #[doc(alias = "--patch-module")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
+    patch_modules: Vec<OsString>,
     /// Enable generation of metadata on method parameters
     /// such that the reflection API can be used to retrieve parameter info.
     method_paramater_metadata: bool,
@@ -57,18 +102,44 @@ pub struct JavaBuild {
     /// If not provided, class files will be placed
     /// in the same directory as the source files.
     #[doc(alias = "-d")]
-    classes_out_dir: Option<OsString>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::option"))]
+    pub(crate) classes_out_dir: Option<OsString>,
     /// Specify where to place generated source files.
     #[doc(alias = "-s")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::option"))]
     sources_out_dir: Option<OsString>,
     /// Specify where to place generated native header files.
     #[doc(alias = "-h")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::option"))]
     headers_out_dir: Option<OsString>,
     /// Pass an option to an annotation processor.
     #[doc(alias = "-A")]
     annotation_parameters: Vec<(String, String)>,
+    /// Pass a valueless option to an annotation processor.
+    #[doc(alias = "-A")]
+    annotation_flags: Vec<String>,
     /// Paths to the java source files to be compiled.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     files: Vec<OsString>,
+    /// Paths to files that list the java source files to be compiled,
+    /// which are passed to javac as `@file` arguments.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
+    source_list_files: Vec<OsString>,
+    /// Override the separator used to join multiple entries in a class path.
+    /// If not provided, the current platform's separator is used.
+    classpath_separator: Option<char>,
+    /// The maximum duration that `javac` is allowed to run for.
+    timeout: Option<Duration>,
+    /// If `true`, print the command instead of executing it.
+    dry_run: bool,
+    /// If `true`, warn when boot class paths are used with a JDK that deprecates them.
+    warn_deprecated_boot_classpath: bool,
+    /// If `true`, prepend the JDK and Android SDK tool directories to the `PATH`.
+    augment_path: bool,
+    /// The maximum number of `javac` processes run at once by `compile_roots()`.
+    max_parallel: Option<usize>,
+    /// The charset in which `javac` should encode its stdout and stderr.
+    output_encoding: Option<String>,
 }
 
 /// Debug information to include in the output of a `javac` build.
@@ -77,11 +148,24 @@ pub struct JavaBuild {
 /// meaning all debug information is included.
 /// This is only relevant *if* you set the `debug_info` field in [`JavaBuild`].
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct DebugInfo {
     pub line_numbers: bool,
     pub variables: bool,
     pub source_files: bool,
 }
+/// Controls whether `javac` generates class files for source files that are
+/// implicitly loaded (e.g., found on the source path), rather than explicitly given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Implicit {
+    /// Do not generate class files for implicitly loaded source files ("-implicit:none").
+    None,
+    /// Generate class files for implicitly loaded source files ("-implicit:class").
+    Class,
+}
+
 impl Default for DebugInfo {
     fn default() -> Self {
         Self {
@@ -92,6 +176,22 @@ impl Default for DebugInfo {
     }
 }
 impl DebugInfo {
+    /// Returns a `DebugInfo` that includes all debug information.
+    ///
+    /// This is the same as [`DebugInfo::default()`].
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Returns a `DebugInfo` that includes no debug information at all ("-g:none").
+    pub fn none() -> Self {
+        Self {
+            line_numbers: false,
+            variables: false,
+            source_files: false,
+        }
+    }
+
     fn add_as_args_to<'c>(&self, cmd: &'c mut Command) -> &'c mut Command {
         if self.line_numbers {
             cmd.arg("-g:lines");
@@ -109,6 +209,18 @@ impl DebugInfo {
     }
 }
 
+/// An independent set of Java source files and the directory
+/// in which their class files should be placed.
+///
+/// See [`JavaBuild::compile_roots()`].
+#[derive(Clone, Debug, Default)]
+pub struct SourceRoot {
+    /// The Java source files to be compiled.
+    pub files: Vec<PathBuf>,
+    /// Where to place the class files generated from `files`.
+    pub out_dir: PathBuf,
+}
+
 impl JavaBuild {
     /// Creates a new `JavaBuild` instance with default values,
     /// which can be further customized using the builder methods.
@@ -116,47 +228,256 @@ impl JavaBuild {
         Default::default()
     }
 
+    /// Creates a new `JavaBuild` instance that is pre-configured
+    /// using the environment variables documented at the crate level.
+    ///
+    /// Specifically, this sets:
+    /// * the Java home directory, via [`java_home_with_javac()`](crate::java_home_with_javac),
+    ///   which honors `JAVA_HOME`.
+    /// * the source version, from `JAVA_SOURCE_VERSION`.
+    /// * the target version, from `JAVA_TARGET_VERSION`.
+    ///
+    /// If both versions are set to the same Java version, they are instead applied
+    /// via [`JavaBuild::target_android_java()`], which uses `--release` if the JDK
+    /// supports it, such that the classes are also compiled against that version's API.
+    ///
+    /// Any of these can be further overridden using the builder methods.
+    pub fn from_env() -> Self {
+        let mut java_build = Self {
+            java_home: env_paths::java_home_with_javac(),
+            ..Default::default()
+        };
+        let source_version = env_paths::java_source_version();
+        let target_version = env_paths::java_target_version();
+        let same_version = source_version.as_deref()
+            .and_then(env_paths::parse_major_version)
+            .filter(|&v| target_version.as_deref().and_then(env_paths::parse_major_version) == Some(v));
+        match same_version {
+            Some(version) => { java_build.target_android_java(version); }
+            None => {
+                java_build.source_version = source_version;
+                java_build.target_version = target_version;
+            }
+        }
+        java_build
+    }
+
     /// Executes the `javac` command based on this `JavaBuild` instance.
+    ///
+    /// If a [timeout](JavaBuild::timeout()) is set and `javac` runs for longer than that,
+    /// it is killed and an error of kind [`std::io::ErrorKind::TimedOut`] is returned.
     pub fn compile(&self) -> std::io::Result<ExitStatus> {
-        self.command()?.status()
+        let mut cmd = self.command()?;
+        if self.dry_run {
+            return Ok(crate::process::dry_run(&cmd));
+        }
+        crate::process::status_with_timeout(&mut cmd, self.timeout, None)
+    }
+
+    /// Executes the `javac` command based on this `JavaBuild` instance,
+    /// capturing its output and parsing it into structured [`Diagnostic`]s.
+    ///
+    /// Unlike [`JavaBuild::compile()`], the diagnostics are not printed.
+    /// A [timeout](JavaBuild::timeout()) is not applied here.
+    pub fn compile_diagnostics(&self) -> std::io::Result<(ExitStatus, Vec<Diagnostic>)> {
+        let mut cmd = self.command()?;
+        if self.dry_run {
+            return Ok((crate::process::dry_run(&cmd), Vec::new()));
+        }
+        let output = cmd.output()?;
+        let diagnostics = crate::parse_javac_diagnostics(&String::from_utf8_lossy(&output.stderr));
+        Ok((output.status, diagnostics))
+    }
+
+    /// Compiles each of the given independent source roots in parallel,
+    /// using a separate `javac` process for each one.
+    ///
+    /// Each root is compiled with a copy of this `JavaBuild`'s configuration,
+    /// but with its own files and classes output directory in place of any
+    /// files or `classes_out_dir` set on this `JavaBuild`.
+    /// At most [`max_parallel`](JavaBuild::max_parallel()) `javac` processes run at once.
+    ///
+    /// All roots are compiled even if some of them fail, after which
+    /// a single error describing every failure is returned.
+    pub fn compile_roots(&self, roots: &[SourceRoot]) -> std::io::Result<()> {
+        let max_parallel = self.max_parallel
+            .or_else(|| std::thread::available_parallelism().ok().map(usize::from))
+            .unwrap_or(1)
+            .clamp(1, roots.len().max(1));
+        let next_root = std::sync::atomic::AtomicUsize::new(0);
+        let errors = std::sync::Mutex::new(Vec::new());
+
+        std::thread::scope(|scope| {
+            for _ in 0..max_parallel {
+                scope.spawn(|| loop {
+                    let i = next_root.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    let Some(root) = roots.get(i) else { break };
+                    let result = self.clone()
+                        .clear_files()
+                        .files(&root.files)
+                        .classes_out_dir(&root.out_dir)
+                        .compile_checked();
+                    if let Err(e) = result {
+                        errors.lock().unwrap().push((i, format!(
+                            "Failed to compile source root {}: {e}", root.out_dir.display()
+                        )));
+                    }
+                });
+            }
+        });
+
+        let mut errors = errors.into_inner().unwrap();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        errors.sort();
+        Err(std::io::Error::other(
+            errors.into_iter().map(|(_, e)| e).collect::<Vec<_>>().join("\n")
+        ))
+    }
+
+    /// Same as [`JavaBuild::compile()`], but also returns how long `javac` took to run.
+    ///
+    /// This is useful for finding out which steps of a slow build script
+    /// are responsible for most of its run time.
+    pub fn timed_compile(&self) -> std::io::Result<(ExitStatus, Duration)> {
+        let start = std::time::Instant::now();
+        let status = self.compile()?;
+        Ok((status, start.elapsed()))
+    }
+
+    /// Executes the `javac` command based on this `JavaBuild` instance,
+    /// returning an error if `javac` does not exit successfully.
+    ///
+    /// The error message includes the exit code of `javac`;
+    /// its diagnostics are printed to the inherited stderr as usual.
+    pub fn compile_checked(&self) -> std::io::Result<()> {
+        crate::process::check_status("javac", self.compile()?, None)
+    }
+
+    /// Returns the paths of all class files in the configured `classes_out_dir`,
+    /// sorted by path.
+    ///
+    /// This is intended to be called after [`JavaBuild::compile()`] to obtain
+    /// the generated class files, e.g., to pass them on to a DEX compiler.
+    /// Returns an error if no `classes_out_dir` has been set.
+    pub fn generated_class_files(&self) -> std::io::Result<Vec<PathBuf>> {
+        let out_dir = self.classes_out_dir.as_ref().ok_or_else(|| std::io::Error::other(
+            "No classes output directory was specified."
+        ))?;
+        crate::files::collect_files_by_ext(out_dir, "class")
+    }
+
+    /// Returns a human-readable string of the `javac` command
+    /// that would be executed based on this `JavaBuild` instance.
+    ///
+    /// This is intended for display and debugging purposes only,
+    /// not for execution by a shell.
+    pub fn command_string(&self) -> std::io::Result<String> {
+        Ok(crate::process::command_string(&self.command()?))
+    }
+
+    /// Returns the fully-resolved `javac` command as plain data,
+    /// i.e., the same program and arguments as [`JavaBuild::command()`].
+    ///
+    /// Unlike a [`Command`], a [`ResolvedCommand`] can be inspected, compared,
+    /// and serialized, e.g., to be handed off to a task runner.
+    pub fn to_owned_command(&self) -> std::io::Result<ResolvedCommand> {
+        Ok(ResolvedCommand::from(&self.command()?))
     }
 
     /// Returns a [`Command`] based on this `JavaBuild` instance
     /// that can be inspected or customized before being executed.
+    ///
+    /// If a source, target, or release version is set, this returns an error
+    /// if the source version is newer than the target version, or if the JDK in use
+    /// cannot compile for the requested version because it is too old or too new.
+    /// This requires running `javac -version`, which is only done once per JDK.
+    ///
+    /// This also returns an error if a release version is set along with any boot class paths,
+    /// as `javac` does not accept `--release` together with `-bootclasspath`.
     pub fn command(&self) -> std::io::Result<Command> {
         let jh_clone = self.java_home.clone();
         let java_home = jh_clone
             .and_then(PathExt::path_if_exists)
-            .or_else(env_paths::java_home)
+            .or_else(env_paths::java_home_with_javac)
             .ok_or_else(|| std::io::Error::other(
-                "JAVA_HOME not provided, and could not be auto-discovered."
+                "JAVA_HOME not provided, and no JDK containing `javac` could be auto-discovered."
             ))?;
 
+        if self.release_version.is_some() && !self.boot_class_paths.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "`--release` cannot be used together with `-bootclasspath`; \
+                set a source and target version instead, or add the boot class paths to the class path.",
+            ));
+        }
+
+        self.validate_versions(&java_home)?;
+
+        if self.warn_deprecated_boot_classpath && !self.boot_class_paths.is_empty() {
+            if let Some(version) = env_paths::cached_javac_version(&java_home) {
+                if version >= 9 {
+                    crate::process::build_warning(&format!(
+                        "`-bootclasspath` is deprecated by javac {version}; \
+                        consider using `--release` or targeting an older JDK instead."
+                    ));
+                }
+            }
+        }
+
         let mut cmd = Command::new(java_home.join("bin").join("javac"));
+        if self.augment_path {
+            if let Some(path) = env_paths::augmented_path(&java_home) {
+                cmd.env("PATH", path);
+            }
+        }
+        if let Some(charset) = self.output_encoding.as_deref() {
+            crate::process::output_encoding_properties(charset).iter()
+                .for_each(|prop| { cmd.arg(format!("-J{}", prop)); });
+        }
         if let Some(d) = self.debug_info.as_ref() {
             d.add_as_args_to(&mut cmd);
         }
 
-        if let Some(source) = env_paths::java_source_version() {
-            cmd.arg("--source").arg(source);
-        }
+        if let Some(release) = self.release_version.as_ref() {
+            cmd.arg("--release").arg(release);
+        } else {
+            if let Some(source) = self.source_version.clone().or_else(env_paths::java_source_version) {
+                cmd.arg("--source").arg(source);
+            }
 
-        if let Some(target) = env_paths::java_target_version() {
-            cmd.arg("--target").arg(target);
+            if let Some(target) = self.target_version.clone().or_else(env_paths::java_target_version) {
+                cmd.arg("--target").arg(target);
+            }
         }
 
-        self.class_paths     .iter().for_each(|p| { cmd.arg("-cp").arg(p); });
-        self.source_paths    .iter().for_each(|p| { cmd.arg("-sourcepath").arg(p); });
-        self.boot_class_paths.iter().for_each(|p| { cmd.arg("-bootclasspath").arg(p); });
-        self.extension_dirs  .iter().for_each(|p| { cmd.arg("-extdirs").arg(p); });
+        // `javac` only honors the last occurrence of each path option,
+        // so multiple entries must be joined into a single argument.
+        let separator = self.effective_classpath_separator();
+        for (flag, paths) in [
+            ("-cp",                     &self.effective_class_paths()),
+            ("-sourcepath",             &self.source_paths),
+            ("-bootclasspath",          &self.boot_class_paths),
+            ("-extdirs",                &self.extension_dirs),
+            ("-processorpath",          &self.annotation_processor_paths),
+            ("--processor-module-path", &self.annotation_processor_module_paths),
+        ] {
+            if !paths.is_empty() {
+                cmd.arg(flag).arg(paths.join(OsStr::new(&separator)));
+            }
+        }
 
         let processors = self.annotation_processors.join(OsStr::new(","));
-        if processors.len() != 0 {
+        if !processors.is_empty() {
             cmd.arg("-processor").arg(processors); 
         }
 
-        self.annotation_processor_paths.iter()
-            .for_each(|p| { cmd.arg("-processorpath").arg(p); });
+        if !self.limit_modules.is_empty() {
+            cmd.arg("--limit-modules").arg(self.limit_modules.join(","));
+        }
+        self.patch_modules.iter()
+            .for_each(|patch| { cmd.arg("--patch-module").arg(patch); });
 
         for (flag, dir) in [
             ("-d", self.classes_out_dir.as_ref()),
@@ -168,6 +489,20 @@ impl JavaBuild {
             }
         }
 
+        if let Some(implicit) = self.implicit {
+            cmd.arg(match implicit {
+                Implicit::None  => "-implicit:none",
+                Implicit::Class => "-implicit:class",
+            });
+        }
+
+        if !self.xlint_enabled.is_empty() || !self.xlint_disabled.is_empty() {
+            let categories = self.xlint_enabled.iter().cloned()
+                .chain(self.xlint_disabled.iter().map(|c| format!("-{}", c)))
+                .collect::<Vec<_>>();
+            cmd.arg(format!("-Xlint:{}", categories.join(",")));
+        }
+
         for (flag, cond) in [
             ("-nowarn",          self.nowarn),
             ("-verbose",         self.verbose),
@@ -181,11 +516,107 @@ impl JavaBuild {
 
         self.annotation_parameters.iter()
             .for_each(|(k,v)| { cmd.arg(format!("-A{}={}", k, v)); });
+        self.annotation_flags.iter()
+            .for_each(|k| { cmd.arg(format!("-A{}", k)); });
+        if self.inherit_java_opts {
+            cmd.args(env_paths::javac_opts_env());
+        }
         self.files.iter().for_each(|f| { cmd.arg(f); });
+        for list_file in &self.source_list_files {
+            if !Path::new(list_file).is_file() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Source list file {:?} does not exist.", list_file),
+                ));
+            }
+            let mut arg = OsString::from("@");
+            arg.push(list_file);
+            cmd.arg(arg);
+        }
 
         Ok(cmd)
     }
 
+    /// Checks that the source version is not newer than the target version,
+    /// and that the JDK in the given `java_home` can compile for the requested versions,
+    /// i.e., that they are neither newer than the JDK nor older than it still supports.
+    ///
+    /// The version of `javac` is only determined once per `java_home`.
+    ///
+    /// Versions that cannot be parsed are left for `javac` itself to reject.
+    fn validate_versions(&self, java_home: &Path) -> std::io::Result<()> {
+        let (source, target) = match self.release_version.as_ref() {
+            Some(release) => (Some(release.clone()), None),
+            None => (
+                self.source_version.clone().or_else(env_paths::java_source_version),
+                self.target_version.clone().or_else(env_paths::java_target_version),
+            ),
+        };
+        let source_major = source.as_deref().and_then(env_paths::parse_major_version);
+        let target_major = target.as_deref().and_then(env_paths::parse_major_version);
+
+        if let (Some(source_major), Some(target_major)) = (source_major, target_major) {
+            if source_major > target_major {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "The source version ({}) must not be newer than the target version ({}).",
+                        source.unwrap_or_default(), target.unwrap_or_default(),
+                    ),
+                ));
+            }
+        }
+
+        let newest = source_major.max(target_major);
+        let oldest = source_major.into_iter().chain(target_major).min();
+        if let (Some(newest), Some(oldest)) = (newest, oldest) {
+            if let Some(javac_version) = env_paths::cached_javac_version(java_home) {
+                if newest > javac_version {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!(
+                            "Java version {newest} was requested, but the JDK at {} \
+                            only supports up to Java {javac_version}.",
+                            java_home.display(),
+                        ),
+                    ));
+                }
+                if let Some(min_version) = min_supported_java_version(javac_version) {
+                    if oldest < min_version {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            format!(
+                                "Java version {oldest} was requested, but the JDK at {} \
+                                (javac {javac_version}) only supports Java {min_version} and later.",
+                                java_home.display(),
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the class paths to be used, including those from
+    /// the `CLASSPATH` environment variable if requested.
+    fn effective_class_paths(&self) -> Vec<OsString> {
+        let mut class_paths = if self.inherit_classpath_env {
+            env_paths::classpath_env()
+        } else {
+            Vec::new()
+        };
+        class_paths.extend(self.class_paths.iter().cloned());
+        class_paths
+    }
+
+    /// Returns the separator used to join multiple entries in a class path.
+    fn effective_classpath_separator(&self) -> String {
+        self.classpath_separator
+            .map(String::from)
+            .unwrap_or_else(|| env_paths::CLASSPATH_SEPARATOR.into())
+    }
+
     ///////////////////////////////////////////////////////////////////////////
     //////////////////////// Builder methods below ////////////////////////////
     ///////////////////////////////////////////////////////////////////////////
@@ -199,12 +630,63 @@ impl JavaBuild {
     }
 
     /// Set which debug info should be included in the generated class files
+    ///
+    /// If not set, no `-g` flag is passed, so `javac`'s default applies:
+    /// only line numbers and source file info are included, but not local variables.
     #[doc(alias("-g"))]
     pub fn debug_info(&mut self, debug_info: DebugInfo) -> &mut Self {
         self.debug_info = Some(debug_info);
         self
     }
 
+    /// Include either all debug info or no debug info in the generated class files.
+    ///
+    /// * If `true`, this is the same as calling [`JavaBuild::debug_info()`] with [`DebugInfo::all()`].
+    /// * If `false`, this is the same as calling [`JavaBuild::no_debug_info()`].
+    ///
+    /// Classes compiled with full debug info should be DEXed in debug mode
+    /// in order for that debug info to be preserved in the DEX output.
+    pub fn debug(&mut self, debug: bool) -> &mut Self {
+        self.debug_info(if debug { DebugInfo::all() } else { DebugInfo::none() })
+    }
+
+    /// Exclude all debug info from the generated class files.
+    ///
+    /// This is the same as calling [`JavaBuild::debug_info()`] with [`DebugInfo::none()`].
+    #[doc(alias("-g:none"))]
+    pub fn no_debug_info(&mut self) -> &mut Self {
+        self.debug_info(DebugInfo::none())
+    }
+
+    /// Applies preset options suitable for debug builds, mirroring Cargo's `dev` profile.
+    ///
+    /// This sets the following, leaving all other options unchanged:
+    /// * [`debug(true)`](JavaBuild::debug()): include all debug info
+    ///   (`-g:lines`, `-g:vars`, and `-g:source`).
+    /// * [`nowarn(false)`](JavaBuild::nowarn()): show warnings.
+    /// * [`warnings_as_errors(false)`](JavaBuild::warnings_as_errors()):
+    ///   don't let warnings fail the build during development (no `-Werror`).
+    pub fn debug_profile(&mut self) -> &mut Self {
+        self.debug(true)
+            .nowarn(false)
+            .warnings_as_errors(false)
+    }
+
+    /// Applies preset options suitable for release builds, mirroring Cargo's `release` profile.
+    ///
+    /// This sets the following, leaving all other options unchanged:
+    /// * [`no_debug_info()`](JavaBuild::no_debug_info()): exclude all debug info (`-g:none`).
+    /// * [`nowarn(false)`](JavaBuild::nowarn()): show warnings.
+    /// * [`xlint("all")`](JavaBuild::xlint()): enable all recommended lint warnings (`-Xlint:all`),
+    ///   unless that category was already enabled.
+    pub fn release_profile(&mut self) -> &mut Self {
+        if !self.xlint_enabled.iter().any(|category| category == "all") {
+            self.xlint("all");
+        }
+        self.no_debug_info()
+            .nowarn(false)
+    }
+
     /// If set to `true`, all warnings are disabled.
     pub fn nowarn(&mut self, nowarn: bool) -> &mut Self {
         self.nowarn = nowarn;
@@ -217,6 +699,27 @@ impl JavaBuild {
         self
     }
 
+    /// Enable the given lint category, e.g., `all`, `unchecked`, or `deprecation`.
+    ///
+    /// All enabled and [disabled](JavaBuild::xlint_disable()) categories are combined
+    /// into a single `-Xlint:` argument, with the enabled categories first,
+    /// each in the order they were added, e.g., `-Xlint:all,-processing`.
+    #[doc(alias("-Xlint"))]
+    pub fn xlint<S: Into<String>>(&mut self, category: S) -> &mut Self {
+        self.xlint_enabled.push(category.into());
+        self
+    }
+
+    /// Disable the given lint category, e.g., `processing` or `serial`.
+    ///
+    /// The category is emitted with a `-` prefix after all enabled categories,
+    /// such that it overrides a broad category like `all`.
+    #[doc(alias("-Xlint"))]
+    pub fn xlint_disable<S: Into<String>>(&mut self, category: S) -> &mut Self {
+        self.xlint_disabled.push(category.into());
+        self
+    }
+
     /// Configure the output about `deprecation` usage.
     ///
     /// * If `true`, javac will output full descriptions of all places
@@ -233,6 +736,69 @@ impl JavaBuild {
         self
     }
 
+    /// Set whether class files are generated for implicitly loaded source files,
+    /// i.e., those found on the source path rather than explicitly given.
+    ///
+    /// If not set, `javac`'s default behavior is used, which generates class files
+    /// but warns if annotation processing is also enabled.
+    #[doc(alias("-implicit"))]
+    pub fn implicit(&mut self, implicit: Implicit) -> &mut Self {
+        self.implicit = Some(implicit);
+        self
+    }
+
+    /// Set the Java version for source compatibility, e.g., `8` or `17`.
+    ///
+    /// If not set, the value of the `JAVA_SOURCE_VERSION` env var is used, if any.
+    #[doc(alias("--source", "-source"))]
+    pub fn source_version<S: Into<String>>(&mut self, source_version: S) -> &mut Self {
+        self.source_version = Some(source_version.into());
+        self
+    }
+
+    /// Set the Java version for target compatibility, e.g., `8` or `17`.
+    ///
+    /// If not set, the value of the `JAVA_TARGET_VERSION` env var is used, if any.
+    #[doc(alias("--target", "-target"))]
+    pub fn target_version<S: Into<String>>(&mut self, target_version: S) -> &mut Self {
+        self.target_version = Some(target_version.into());
+        self
+    }
+
+    /// Set the Java version to compile for, e.g., `8` or `17`, which requires JDK 9 or later.
+    ///
+    /// Unlike setting the source and target versions, this also compiles
+    /// against the public API of that Java version.
+    /// If set, the source and target versions (and their env vars) are ignored.
+    #[doc(alias("--release"))]
+    pub fn release_version<S: Into<String>>(&mut self, release_version: S) -> &mut Self {
+        self.release_version = Some(release_version.into());
+        self
+    }
+
+    /// Compile for the given Java `version`, e.g., `8`, using whichever flags
+    /// the JDK in use supports, which avoids class file version mismatches
+    /// when the resulting classes are later converted to DEX.
+    ///
+    /// This runs `javac -version` (see [`check_javac_version()`](crate::check_javac_version)):
+    /// * On JDK 9 or later, `--release <version>` is used.
+    /// * On older JDKs, or if the version cannot be determined,
+    ///   `--source <version>` and `--target <version>` are used instead.
+    pub fn target_android_java(&mut self, version: u32) -> &mut Self {
+        let javac_version = self.java_home.clone()
+            .and_then(PathExt::path_if_exists)
+            .or_else(env_paths::java_home_with_javac)
+            .and_then(|jh| env_paths::cached_javac_version(&jh));
+        match javac_version {
+            Some(javac_version) if javac_version >= 9 && version <= javac_version => {
+                self.release_version(version.to_string())
+            }
+            _ => self
+                .source_version(version.to_string())
+                .target_version(version.to_string()),
+        }
+    }
+
     /// Specify where to find user class files and annotation processors.
     ///
     /// If no class paths are provided, the current directory will be used.
@@ -241,6 +807,51 @@ impl JavaBuild {
         self
     }
 
+    /// Adds multiple class path entries at once.
+    ///
+    /// This is the same as calling [`JavaBuild::class_path()`] multiple times.
+    pub fn class_paths<P>(&mut self, class_paths: P) -> &mut Self
+    where
+        P: IntoIterator,
+        P::Item: AsRef<OsStr>,
+    {
+        self.class_paths.extend(class_paths.into_iter().map(|p| p.as_ref().into()));
+        self
+    }
+
+    /// If set to `true`, the entries in the `CLASSPATH` environment variable
+    /// will be prepended to the class paths given via [`JavaBuild::class_path()`].
+    ///
+    /// This is disabled by default, in which case `javac` itself only consults
+    /// `CLASSPATH` if no class paths were provided at all.
+    pub fn inherit_classpath_env(&mut self, inherit_classpath_env: bool) -> &mut Self {
+        self.inherit_classpath_env = inherit_classpath_env;
+        self
+    }
+
+    /// If set to `true`, the options in the `ANDROID_JAVAC_OPTS` environment variable
+    /// are passed to `javac` after all other options, just before the source files.
+    ///
+    /// Options are split on whitespace, with `'` and `"` quoting supported,
+    /// but no other shell features. Note that this passes arbitrary options
+    /// from the environment to `javac`, so only enable this if the environment is trusted.
+    pub fn inherit_java_opts(&mut self, inherit_java_opts: bool) -> &mut Self {
+        self.inherit_java_opts = inherit_java_opts;
+        self
+    }
+
+    /// Override the separator used to join multiple entries in a path option,
+    /// such as the class path, source path, or boot class path.
+    ///
+    /// By default, the current platform's separator is used: `;` on Windows, `:` elsewhere.
+    /// Overriding this is only necessary when generating a command that will be run
+    /// on a different platform than the current one, e.g., inside WSL targeting
+    /// a Windows `javac.exe` with Windows-style paths.
+    pub fn classpath_separator(&mut self, separator: char) -> &mut Self {
+        self.classpath_separator = Some(separator);
+        self
+    }
+
     /// Specify where to find input source files.
     ///
     /// If not specified, `class_paths` will be searched for source files.
@@ -257,6 +868,48 @@ impl JavaBuild {
         self
     }
 
+    /// Sets the `android.jar` for the given Android API level as a boot class path,
+    /// which is the classic way to compile Java code against the Android platform APIs.
+    ///
+    /// If `api_level` is `Some`, the `android.jar` is found using
+    /// [`android_jar_for_api()`](crate::android_jar_for_api), which honors `ANDROID_SDK_EXTENSION`.
+    /// If `api_level` is `None`, the `android.jar` is found using [`android_jar()`](crate::android_jar)
+    /// based on the environment variables documented at the crate level.
+    ///
+    /// Note that on JDK 9 and later, `-bootclasspath` can only be used when targeting
+    /// Java 8 or earlier; a warning is emitted if the JDK in use is that new
+    /// and no such target version is set. It also cannot be combined with a
+    /// [release version](JavaBuild::release_version), which makes [`JavaBuild::command()`] fail.
+    /// In that case, consider adding `android.jar` via [`JavaBuild::class_path()`] instead.
+    pub fn android_bootclasspath(&mut self, api_level: Option<u32>) -> std::io::Result<&mut Self> {
+        let android_jar = match api_level {
+            Some(api_level) => env_paths::android_jar_for_api(api_level, None),
+            None => env_paths::android_jar(None),
+        };
+        let android_jar = android_jar.ok_or_else(|| std::io::Error::other(
+            "Could not find android.jar for the requested Android platform."
+        ))?;
+
+        let java_home = self.java_home.clone()
+            .and_then(PathExt::path_if_exists)
+            .or_else(env_paths::java_home_with_javac);
+        let targets_java_8 = self.release_version.is_none() && self.target_version.clone()
+            .or_else(env_paths::java_target_version)
+            .and_then(|target| env_paths::parse_major_version(&target))
+            .is_some_and(|target| target <= 8);
+        if let Some(version) = java_home.and_then(|jh| env_paths::cached_javac_version(&jh)) {
+            if version >= 9 && !targets_java_8 {
+                crate::process::build_warning(&format!(
+                    "Using `-bootclasspath` with javac {version} requires `--source`/`--target` 8 or lower; \
+                    consider using `--release` and adding android.jar to the class path instead."
+                ));
+            }
+        }
+
+        self.boot_class_paths.push(android_jar.into_os_string());
+        Ok(self)
+    }
+
     /// Specify where to find installed extensions.
     ///
     /// If set, this will override the default search locations.
@@ -289,6 +942,15 @@ impl JavaBuild {
         self
     }
 
+    /// Add a path to search for annotation processors that are packaged as modules.
+    ///
+    /// This is the modular alternative to [`JavaBuild::annotation_processor_path()`].
+    #[doc(alias("--processor-module-path"))]
+    pub fn annotation_processor_module_path<P: AsRef<OsStr>>(&mut self, path: P) -> &mut Self {
+        self.annotation_processor_module_paths.push(path.as_ref().into());
+        self
+    }
+
     /// Enable generation of metadata on method parameters
     /// such that the reflection API can be used to retrieve parameter info.
     pub fn method_paramater_metadata(&mut self, method_paramater_metadata: bool) -> &mut Self {
@@ -306,6 +968,22 @@ impl JavaBuild {
         self
     }
 
+    /// Place generated class files in the given `subdir` of Cargo's `OUT_DIR`,
+    /// creating that directory if it doesn't yet exist.
+    ///
+    /// This is only meaningful within a build script, and returns an error
+    /// if the `OUT_DIR` environment variable is not set.
+    #[cfg(feature = "cargo")]
+    pub fn out_dir_in_cargo_out<P: AsRef<Path>>(&mut self, subdir: P) -> std::io::Result<&mut Self> {
+        let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "OUT_DIR is not set; this must be called from within a Cargo build script.",
+        ))?;
+        let classes_out_dir = Path::new(&out_dir).join(subdir);
+        std::fs::create_dir_all(&classes_out_dir)?;
+        Ok(self.classes_out_dir(classes_out_dir))
+    }
+
     /// Specify where to place generated source files.
     #[doc(alias("-s"))]
     pub fn sources_out_dir<P: AsRef<OsStr>>(&mut self, sources_out_dir: P) -> &mut Self {
@@ -320,6 +998,38 @@ impl JavaBuild {
         self
     }
 
+    /// Limit the universe of observable modules to the given modules
+    /// (and their transitive dependencies).
+    ///
+    /// This can be called multiple times; all modules are emitted as
+    /// a single comma-separated `--limit-modules` list.
+    #[doc(alias("--limit-modules"))]
+    pub fn limit_modules<I>(&mut self, modules: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.limit_modules.extend(modules.into_iter().map(Into::into));
+        self
+    }
+
+    /// Override or augment the given `module` with the classes and resources at `path`,
+    /// which is typically used to compile tests into an existing module.
+    ///
+    /// This can be called multiple times to patch multiple modules.
+    #[doc(alias("--patch-module"))]
+    pub fn patch_module<M, P>(&mut self, module: M, path: P) -> &mut Self
+    where
+        M: Into<String>,
+        P: AsRef<OsStr>,
+    {
+        let mut patch = OsString::from(module.into());
+        patch.push("=");
+        patch.push(path);
+        self.patch_modules.push(patch);
+        self
+    }
+
     /// Add a key-value pair to be passed as an option to an annotation processor.
     #[doc(alias("-A"))]
     pub fn annotation_parameter<K, V>(&mut self, key: K, value: V) -> &mut Self 
@@ -331,12 +1041,85 @@ impl JavaBuild {
         self
     }
 
+    /// Add a key without a value to be passed as an option to an annotation processor,
+    /// e.g., `-Adebug`, for processors that accept boolean-style options.
+    #[doc(alias("-A"))]
+    pub fn annotation_flag<K: Into<String>>(&mut self, key: K) -> &mut Self {
+        self.annotation_flags.push(key.into());
+        self
+    }
+
     /// If set to `true`, warnings are treated as compilation errors.
     pub fn warnings_as_errors(&mut self, warnings_as_errors: bool) -> &mut Self {
         self.warnings_as_errors = warnings_as_errors;
         self
     }
 
+    /// If set to `true`, [`JavaBuild::compile()`] prints the `javac` command that would be run
+    /// instead of executing it, and returns a successful exit status.
+    ///
+    /// The command is printed as a Cargo warning if the `cargo` feature is enabled,
+    /// or to stderr otherwise.
+    pub fn dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// If set to `true`, the `PATH` of the `javac` process is the current `PATH`
+    /// with the following directories prepended to it, if they exist:
+    /// * the `bin` directory of the JDK in use,
+    /// * the Android SDK's `platform-tools` directory, and
+    /// * the Android SDK's `build-tools` directory given by [`build_tools_dir()`](crate::build_tools_dir).
+    ///
+    /// This is useful when `javac` or code it runs (e.g., annotation processors)
+    /// invokes other tools by name.
+    pub fn augment_path(&mut self, augment_path: bool) -> &mut Self {
+        self.augment_path = augment_path;
+        self
+    }
+
+    /// If set to `true`, [`JavaBuild::command()`] checks the version of `javac`
+    /// whenever boot class paths have been given, and prints a warning if that
+    /// version (JDK 9 or later) deprecates `-bootclasspath` in favor of `--release`.
+    ///
+    /// This is disabled by default, as it requires running `javac -version`.
+    /// The warning is printed as a Cargo warning if the `cargo` feature is enabled,
+    /// or logged as a warning otherwise.
+    pub fn warn_deprecated_boot_classpath(&mut self, warn: bool) -> &mut Self {
+        self.warn_deprecated_boot_classpath = warn;
+        self
+    }
+
+    /// Set the charset in which `javac` encodes its diagnostics on stdout and stderr, e.g., `UTF-8`.
+    ///
+    /// This is useful when capturing the output of [`JavaBuild::command()`] on Windows,
+    /// where `javac` otherwise uses the console's code page, which results in
+    /// garbled text when the output is decoded as UTF-8.
+    /// If not set, the JVM's default encoding is used.
+    #[doc(alias("-J-Dstdout.encoding"))]
+    pub fn output_encoding<S: Into<String>>(&mut self, charset: S) -> &mut Self {
+        self.output_encoding = Some(charset.into());
+        self
+    }
+
+    /// Set the maximum number of `javac` processes that [`JavaBuild::compile_roots()`]
+    /// runs at once.
+    ///
+    /// If not set, the available parallelism of the current machine is used.
+    pub fn max_parallel(&mut self, max_parallel: usize) -> &mut Self {
+        self.max_parallel = Some(max_parallel);
+        self
+    }
+
+    /// Set the maximum duration that `javac` is allowed to run for
+    /// when invoked via [`JavaBuild::compile()`].
+    ///
+    /// If not set, `javac` is allowed to run indefinitely.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     /// Adds a Java source file to be compiled by javac.
     #[doc(alias("source file"))]
     pub fn file<P: AsRef<OsStr>>(&mut self, file: P) -> &mut Self {
@@ -356,4 +1139,682 @@ impl JavaBuild {
         self.files.extend(files.into_iter().map(|f| f.as_ref().into()));
         self
     }
+
+    /// Recursively adds all Java source files (`*.java`) within the given directory
+    /// for which the given `predicate` returns `true`, in order of their paths.
+    ///
+    /// This is useful for excluding some sources, e.g., those in a `test` subdirectory:
+    /// `.collect_sources_filtered("src", |path| !path.starts_with("src/test"))`.
+    pub fn collect_sources_filtered<P, F>(&mut self, dir: P, mut predicate: F) -> std::io::Result<&mut Self>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&Path) -> bool,
+    {
+        let sources = crate::files::collect_files_by_ext(dir, "java")?;
+        Ok(self.files(sources.into_iter().filter(|path| predicate(path))))
+    }
+
+    /// Adds a file that lists Java source files to be compiled by javac,
+    /// which is passed to javac as an `@file` argument.
+    ///
+    /// This is useful when another build system already generates a list of source files.
+    /// The file should contain one source file path per line (or be separated by whitespace),
+    /// and must exist by the time [`JavaBuild::command()`] is called.
+    #[doc(alias("@file", "argfile"))]
+    pub fn source_list_file<P: AsRef<OsStr>>(&mut self, source_list_file: P) -> &mut Self {
+        self.source_list_files.push(source_list_file.as_ref().into());
+        self
+    }
+
+    /// Reads the Java source files listed in the given javac-style `@argfile`
+    /// and adds them to be compiled by javac.
+    ///
+    /// Unlike [`JavaBuild::source_list_file()`], which passes the argfile to javac as is,
+    /// this materializes the list of source files. If the `cargo` feature is enabled,
+    /// each one (as well as the argfile itself) is reported to Cargo via `cargo:rerun-if-changed`.
+    ///
+    /// The argfile follows javac's rules for quoting and line continuations.
+    /// It must only list source files; an error is returned if it contains any options.
+    #[doc(alias("@file", "argfile"))]
+    pub fn add_files_from_argfile<P: AsRef<Path>>(&mut self, argfile: P) -> std::io::Result<&mut Self> {
+        let argfile = argfile.as_ref();
+        let args = crate::files::parse_argfile(&std::fs::read_to_string(argfile)?)?;
+        if let Some(option) = args.iter().find(|arg| arg.starts_with('-') || arg.starts_with('@')) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("The argument file {:?} contains {:?}, but must only list source files.",
+                    argfile, option,
+                ),
+            ));
+        }
+        #[cfg(feature = "cargo")] {
+            println!("cargo:rerun-if-changed={}", argfile.display());
+            for file in &args {
+                println!("cargo:rerun-if-changed={}", file);
+            }
+        }
+        Ok(self.files(args))
+    }
+
+    /// Removes all Java source files (and source list files) that have been added so far,
+    /// while preserving all other configuration options.
+    ///
+    /// This is useful when reusing a `JavaBuild` for multiple compilation units.
+    pub fn clear_files(&mut self) -> &mut Self {
+        self.files.clear();
+        self.source_list_files.clear();
+        self
+    }
+
+    /// Removes and returns all Java source files (and source list files) that have been added so far,
+    /// while preserving all other configuration options.
+    ///
+    /// Source list files are returned after the source files as `@file` arguments,
+    /// so the returned list can be passed as-is to javac or to [`JavaBuild::files()`].
+    pub fn take_files(&mut self) -> Vec<OsString> {
+        let mut files = std::mem::take(&mut self.files);
+        files.extend(self.source_list_files.drain(..).map(|list_file| {
+            let mut arg = OsString::from("@");
+            arg.push(list_file);
+            arg
+        }));
+        files
+    }
+
+    /// Resets this `JavaBuild` to its default configuration,
+    /// as if it were newly created with [`JavaBuild::new()`].
+    ///
+    /// This is useful when reusing a single `JavaBuild` across loop iterations.
+    pub fn reset(&mut self) -> &mut Self {
+        *self = Self::default();
+        self
+    }
+
+    /// Merges the configuration of `other` into this `JavaBuild`.
+    ///
+    /// The precedence rules are as follows:
+    /// * List-based options (files, class paths, annotation processors, etc.)
+    ///   from `other` are appended after the existing entries in `self`.
+    /// * `Option` fields (e.g., `java_home`, `debug_info`, output directories)
+    ///   are overridden by `other` only if they are set (`Some`) in `other`.
+    /// * `bool` flags are enabled if they are enabled in *either* `self` or `other`.
+    ///
+    /// This allows a library to expose a base `JavaBuild` that users can extend.
+    pub fn merge(&mut self, other: &JavaBuild) -> &mut Self {
+        // Destructure `other` such that any newly-added fields must be handled here.
+        let JavaBuild {
+            java_home,
+            debug_info,
+            nowarn,
+            verbose,
+            xlint_enabled,
+            xlint_disabled,
+            warnings_as_errors,
+            deprecation,
+            enable_preview_features,
+            implicit,
+            source_version,
+            target_version,
+            release_version,
+            class_paths,
+            inherit_classpath_env,
+            inherit_java_opts,
+            source_paths,
+            boot_class_paths,
+            extension_dirs,
+            endorsed_dirs,
+            annotation_processors,
+            annotation_processor_paths,
+            annotation_processor_module_paths,
+            limit_modules,
+            patch_modules,
+            method_paramater_metadata,
+            classes_out_dir,
+            sources_out_dir,
+            headers_out_dir,
+            annotation_parameters,
+            annotation_flags,
+            files,
+            source_list_files,
+            timeout,
+            dry_run,
+            warn_deprecated_boot_classpath,
+            augment_path,
+            max_parallel,
+            output_encoding,
+            classpath_separator,
+        } = other;
+
+        fn merge_opt<T: Clone>(this: &mut Option<T>, other: &Option<T>) {
+            if other.is_some() {
+                this.clone_from(other);
+            }
+        }
+
+        merge_opt(&mut self.java_home,       java_home);
+        merge_opt(&mut self.debug_info,      debug_info);
+        merge_opt(&mut self.implicit,        implicit);
+        merge_opt(&mut self.source_version,  source_version);
+        merge_opt(&mut self.target_version,  target_version);
+        merge_opt(&mut self.release_version, release_version);
+        merge_opt(&mut self.max_parallel,    max_parallel);
+        merge_opt(&mut self.output_encoding, output_encoding);
+        merge_opt(&mut self.classes_out_dir, classes_out_dir);
+        merge_opt(&mut self.sources_out_dir, sources_out_dir);
+        merge_opt(&mut self.headers_out_dir, headers_out_dir);
+        merge_opt(&mut self.timeout,         timeout);
+        merge_opt(&mut self.classpath_separator, classpath_separator);
+
+        self.nowarn                    |= nowarn;
+        self.verbose                   |= verbose;
+        self.warnings_as_errors        |= warnings_as_errors;
+        self.deprecation               |= deprecation;
+        self.enable_preview_features   |= enable_preview_features;
+        self.inherit_classpath_env     |= inherit_classpath_env;
+        self.inherit_java_opts         |= inherit_java_opts;
+        self.method_paramater_metadata |= method_paramater_metadata;
+        self.dry_run                   |= dry_run;
+        self.warn_deprecated_boot_classpath |= warn_deprecated_boot_classpath;
+        self.augment_path              |= augment_path;
+
+        self.xlint_enabled             .extend_from_slice(xlint_enabled);
+        self.xlint_disabled            .extend_from_slice(xlint_disabled);
+        self.class_paths               .extend_from_slice(class_paths);
+        self.source_paths              .extend_from_slice(source_paths);
+        self.boot_class_paths          .extend_from_slice(boot_class_paths);
+        self.extension_dirs            .extend_from_slice(extension_dirs);
+        self.endorsed_dirs             .extend_from_slice(endorsed_dirs);
+        self.annotation_processors     .extend_from_slice(annotation_processors);
+        self.annotation_processor_paths.extend_from_slice(annotation_processor_paths);
+        self.annotation_processor_module_paths.extend_from_slice(annotation_processor_module_paths);
+        self.limit_modules             .extend_from_slice(limit_modules);
+        self.patch_modules             .extend_from_slice(patch_modules);
+        self.annotation_parameters     .extend_from_slice(annotation_parameters);
+        self.annotation_flags          .extend_from_slice(annotation_flags);
+        self.files                     .extend_from_slice(files);
+        self.source_list_files         .extend_from_slice(source_list_files);
+        self
+    }
+}
+
+/// Returns the oldest Java version that the given major version of `javac`
+/// can compile for via `--source`/`--target` or `--release`,
+/// or `None` if the oldest supported version is unknown (JDK 8 and earlier).
+fn min_supported_java_version(javac_version: u32) -> Option<u32> {
+    match javac_version {
+        ..=8    => None,
+        9..=11  => Some(6),
+        12..=19 => Some(7),
+        20..    => Some(8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Java home directory without a `javac`, such that no version checks can run `javac`.
+    fn fake_java_home() -> crate::TestDir {
+        crate::TestDir::new("no-jdk")
+    }
+
+    /// A Java home directory whose `javac` is a script that reports the given `version`.
+    #[cfg(unix)]
+    fn fake_jdk(version: u32) -> crate::TestDir {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = crate::TestDir::new(&format!("jdk{version}"));
+        std::fs::create_dir_all(dir.join("bin")).unwrap();
+        let javac = dir.join("bin").join("javac");
+        std::fs::write(&javac, format!("#!/bin/sh\necho javac {version}\n")).unwrap();
+        std::fs::set_permissions(&javac, std::fs::Permissions::from_mode(0o755)).unwrap();
+        dir
+    }
+
+    /// Returns the arguments of the `javac` command built by the given `java_build`,
+    /// using a Java home without a `javac` unless one was set.
+    fn args(java_build: &mut JavaBuild) -> Vec<String> {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let java_home = fake_java_home();
+        if java_build.java_home.is_none() {
+            java_build.java_home(&*java_home);
+        }
+        java_build.command().unwrap()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn source_newer_than_target_is_an_error() {
+        let mut java_build = JavaBuild::new();
+        java_build.source_version("11").target_version("1.8");
+        let err = java_build.validate_versions(&fake_java_home()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        java_build.source_version("1.8").target_version("11");
+        assert!(java_build.validate_versions(&fake_java_home()).is_ok());
+        java_build.source_version("8").target_version("8");
+        assert!(java_build.validate_versions(&fake_java_home()).is_ok());
+    }
+
+    #[test]
+    fn release_version_ignores_source_and_target() {
+        let mut java_build = JavaBuild::new();
+        java_build.source_version("17").target_version("8").release_version("11");
+        assert!(java_build.validate_versions(&fake_java_home()).is_ok());
+    }
+
+    #[test]
+    fn min_supported_java_versions() {
+        assert_eq!(min_supported_java_version(8), None);
+        assert_eq!(min_supported_java_version(11), Some(6));
+        assert_eq!(min_supported_java_version(17), Some(7));
+        assert_eq!(min_supported_java_version(21), Some(8));
+    }
+
+    #[test]
+    fn annotation_flags_follow_annotation_parameters() {
+        let mut java_build = JavaBuild::new();
+        java_build.annotation_flag("debug").annotation_parameter("key", "value").file("A.java");
+        let args = args(&mut java_build);
+        assert_eq!(args[args.len() - 3 ..], ["-Akey=value", "-Adebug", "A.java"]);
+    }
+
+    #[test]
+    fn processor_module_paths_are_joined() {
+        let mut java_build = JavaBuild::new();
+        java_build
+            .classpath_separator(':')
+            .annotation_processor_module_path("/procs/a")
+            .annotation_processor_module_path("/procs/b");
+        let args = args(&mut java_build);
+        let index = args.iter().position(|arg| arg == "--processor-module-path").unwrap();
+        assert_eq!(args[index + 1], "/procs/a:/procs/b");
+    }
+
+    #[test]
+    fn limit_and_patch_modules() {
+        let mut java_build = JavaBuild::new();
+        java_build
+            .limit_modules(["java.base", "java.sql"])
+            .limit_modules(["jdk.unsupported"])
+            .patch_module("com.example", "/build/test-classes")
+            .patch_module("java.base", "/patches/base");
+        let args = args(&mut java_build);
+        let index = args.iter().position(|arg| arg == "--limit-modules").unwrap();
+        assert_eq!(args[index ..= index + 5], [
+            "--limit-modules", "java.base,java.sql,jdk.unsupported",
+            "--patch-module", "com.example=/build/test-classes",
+            "--patch-module", "java.base=/patches/base",
+        ]);
+    }
+
+    #[test]
+    fn source_list_files_are_passed_after_files() {
+        let dir = crate::TestDir::new("source-list");
+        let list_file = dir.join("sources.txt");
+        std::fs::write(&list_file, "A.java\n").unwrap();
+        let mut java_build = JavaBuild::new();
+        java_build.source_list_file(&list_file).file("B.java");
+        let args = args(&mut java_build);
+        assert_eq!(args[args.len() - 2 ..], ["B.java".to_string(), format!("@{}", list_file.display())]);
+
+        java_build.clear_files().source_list_file(fake_java_home().join("missing.txt"));
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        assert_eq!(java_build.command().unwrap_err().kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn release_version_supersedes_source_and_target() {
+        let mut java_build = JavaBuild::new();
+        java_build.source_version("8").target_version("8");
+        let args_before = args(&mut java_build);
+        assert!(args_before.windows(2).any(|w| w == ["--source", "8"]));
+        assert!(args_before.windows(2).any(|w| w == ["--target", "8"]));
+
+        java_build.release_version("11");
+        let args = args(&mut java_build);
+        assert!(args.windows(2).any(|w| w == ["--release", "11"]));
+        assert!(!args.iter().any(|arg| arg == "--source" || arg == "--target"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn target_android_java_uses_release_on_jdk9_and_later() {
+        let mut java_build = JavaBuild::new();
+        let jdk = fake_jdk(17);
+        java_build.java_home(&*jdk).target_android_java(8);
+        assert!(args(&mut java_build).windows(2).any(|w| w == ["--release", "8"]));
+
+        let mut java_build = JavaBuild::new();
+        let jdk = fake_jdk(8);
+        java_build.java_home(&*jdk).target_android_java(8);
+        let args = args(&mut java_build);
+        assert!(args.windows(2).any(|w| w == ["--source", "8"]));
+        assert!(!args.iter().any(|arg| arg == "--release"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn versions_unsupported_by_the_jdk_are_errors() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let jdk = fake_jdk(17);
+        let mut java_build = JavaBuild::new();
+        for (version, ok) in [("6", false), ("7", true), ("17", true), ("21", false)] {
+            java_build.release_version(version);
+            assert_eq!(java_build.validate_versions(&jdk).is_ok(), ok, "--release {version}");
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn from_env_prefers_release_for_equal_versions() {
+        let jdk = fake_jdk(17);
+        let from_env = |source: &str, target: &str| {
+            let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+            std::env::remove_var(env_paths::ANDROID_JAVA_HOME);
+            std::env::set_var(env_paths::JAVA_HOME, &jdk);
+            std::env::set_var(env_paths::JAVA_SOURCE_VERSION, source);
+            std::env::set_var(env_paths::JAVA_TARGET_VERSION, target);
+            let java_build = JavaBuild::from_env();
+            std::env::remove_var(env_paths::JAVA_SOURCE_VERSION);
+            std::env::remove_var(env_paths::JAVA_TARGET_VERSION);
+            std::env::remove_var(env_paths::JAVA_HOME);
+            java_build
+        };
+
+        let equal = from_env("1.8", "8");
+        assert_eq!(equal.release_version.as_deref(), Some("8"));
+        assert_eq!((equal.source_version, equal.target_version), (None, None));
+
+        let differing = from_env("8", "11");
+        assert_eq!(differing.release_version, None);
+        assert_eq!(differing.source_version.as_deref(), Some("8"));
+        assert_eq!(differing.target_version.as_deref(), Some("11"));
+    }
+
+    #[test]
+    fn xlint_disabled_categories_follow_enabled_ones() {
+        let mut java_build = JavaBuild::new();
+        java_build.xlint_disable("processing").xlint("all").xlint("unchecked");
+        assert!(args(&mut java_build).contains(&"-Xlint:all,unchecked,-processing".to_string()));
+    }
+
+    #[test]
+    fn implicit_flags() {
+        let mut java_build = JavaBuild::new();
+        assert!(!args(&mut java_build).iter().any(|arg| arg.starts_with("-implicit")));
+        java_build.implicit(Implicit::None);
+        assert!(args(&mut java_build).contains(&"-implicit:none".to_string()));
+        java_build.implicit(Implicit::Class);
+        assert!(args(&mut java_build).contains(&"-implicit:class".to_string()));
+    }
+
+    #[test]
+    fn debug_toggles_all_debug_info() {
+        let mut java_build = JavaBuild::new();
+        assert!(!args(&mut java_build).iter().any(|arg| arg.starts_with("-g")));
+        java_build.debug(true);
+        assert!(args(&mut java_build).starts_with(&["-g:lines".into(), "-g:vars".into(), "-g:source".into()]));
+        java_build.debug(false);
+        assert_eq!(args(&mut java_build)[0], "-g:none");
+    }
+
+    #[test]
+    fn merge_appends_lists_and_overrides_set_options() {
+        let mut base = JavaBuild::new();
+        base.file("A.java").class_path("a.jar").release_version("8").nowarn(true);
+        let mut other = JavaBuild::new();
+        other.file("B.java").class_path("b.jar").release_version("11");
+        base.merge(&other);
+        assert_eq!(base.files, [OsString::from("A.java"), OsString::from("B.java")]);
+        assert_eq!(base.class_paths, [OsString::from("a.jar"), OsString::from("b.jar")]);
+        assert_eq!(base.release_version.as_deref(), Some("11"));
+        assert!(base.nowarn);
+
+        base.merge(&JavaBuild::new());
+        assert_eq!(base.release_version.as_deref(), Some("11"));
+        assert!(base.nowarn);
+    }
+
+    #[test]
+    fn clear_and_take_files_keep_other_options() {
+        let mut java_build = JavaBuild::new();
+        java_build.file("A.java").file("B.java").source_list_file("sources.txt").nowarn(true);
+        assert_eq!(java_build.take_files(), ["A.java", "B.java", "@sources.txt"]);
+        assert!(java_build.files.is_empty() && java_build.source_list_files.is_empty());
+        java_build.file("C.java").clear_files();
+        assert!(java_build.files.is_empty() && java_build.source_list_files.is_empty());
+        assert!(java_build.nowarn);
+    }
+
+    #[test]
+    fn path_options_use_the_separator() {
+        let mut java_build = JavaBuild::new();
+        java_build.class_path("a.jar").class_path("b.jar");
+        let expected = if cfg!(windows) { "a.jar;b.jar" } else { "a.jar:b.jar" };
+        assert!(args(&mut java_build).windows(2).any(|w| w == ["-cp", expected]));
+        java_build.classpath_separator(';');
+        assert!(args(&mut java_build).windows(2).any(|w| w == ["-cp", "a.jar;b.jar"]));
+    }
+
+    #[test]
+    fn class_paths_extends_class_path() {
+        let mut java_build = JavaBuild::new();
+        java_build.class_path("a.jar").class_paths(["b.jar", "c.jar"]);
+        assert_eq!(java_build.class_paths, ["a.jar", "b.jar", "c.jar"].map(OsString::from));
+    }
+
+    #[test]
+    fn reset_restores_defaults() {
+        let mut java_build = JavaBuild::new();
+        java_build.file("A.java").class_path("a.jar").release_version("11").debug(true).reset();
+        let mut default = JavaBuild::new();
+        assert_eq!(args(&mut java_build), args(&mut default));
+        assert!(java_build.files.is_empty());
+    }
+
+    #[test]
+    fn debug_and_release_profiles() {
+        let mut java_build = JavaBuild::new();
+        java_build.nowarn(true).warnings_as_errors(true).debug_profile();
+        let args_debug = args(&mut java_build);
+        assert!(args_debug.starts_with(&["-g:lines".into(), "-g:vars".into(), "-g:source".into()]));
+        assert!(!args_debug.iter().any(|arg| arg == "-nowarn" || arg == "-Werror"));
+
+        java_build.xlint("all").release_profile();
+        let args_release = args(&mut java_build);
+        assert_eq!(args_release[0], "-g:none");
+        assert_eq!(args_release.iter().filter(|arg| arg.starts_with("-Xlint")).collect::<Vec<_>>(), ["-Xlint:all"]);
+    }
+
+    #[test]
+    fn output_encoding_is_passed_to_the_jvm() {
+        let mut java_build = JavaBuild::new();
+        java_build.output_encoding("UTF-8");
+        let args = args(&mut java_build);
+        assert!(args.contains(&"-J-Dstdout.encoding=UTF-8".to_string()));
+        assert!(args.contains(&"-J-Dsun.stderr.encoding=UTF-8".to_string()));
+    }
+
+    #[test]
+    fn debug_info_constructors() {
+        let mut java_build = JavaBuild::new();
+        java_build.debug_info(DebugInfo::none());
+        assert_eq!(args(&mut java_build), ["-g:none"]);
+        java_build.debug_info(DebugInfo { source_files: false, ..DebugInfo::all() });
+        assert_eq!(args(&mut java_build), ["-g:lines", "-g:vars"]);
+    }
+
+    #[test]
+    fn android_bootclasspath_uses_platform_jar() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let sdk = crate::TestDir::new("sdk-bootclasspath");
+        let platform = sdk.join("platforms").join("android-34");
+        std::fs::create_dir_all(&platform).unwrap();
+        std::fs::write(platform.join("android.jar"), b"").unwrap();
+        std::env::set_var(env_paths::ANDROID_HOME, &sdk);
+        let mut java_build = JavaBuild::new();
+        let java_home = fake_java_home();
+        java_build.java_home(&*java_home);
+        let found = java_build.android_bootclasspath(Some(34)).is_ok();
+        let missing = java_build.clone().android_bootclasspath(Some(21)).is_err();
+        std::env::remove_var(env_paths::ANDROID_HOME);
+
+        assert!(found && missing);
+        assert_eq!(java_build.boot_class_paths, [platform.join("android.jar").into_os_string()]);
+    }
+
+    #[test]
+    fn generated_class_files_are_found_recursively() {
+        let out_dir = crate::TestDir::new("classes");
+        std::fs::create_dir_all(out_dir.join("com").join("example")).unwrap();
+        for file in ["com/example/Main.class", "com/example/Main$1.class", "Top.class", "notes.txt"] {
+            std::fs::write(out_dir.join(file), b"").unwrap();
+        }
+        let mut java_build = JavaBuild::new();
+        assert!(java_build.generated_class_files().is_err());
+
+        java_build.classes_out_dir(&out_dir);
+        assert_eq!(java_build.generated_class_files().unwrap(), [
+            out_dir.join("Top.class"),
+            out_dir.join("com/example/Main$1.class"),
+            out_dir.join("com/example/Main.class"),
+        ]);
+    }
+
+    #[test]
+    fn augment_path_prepends_jdk_bin() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let jdk = fake_java_home();
+        std::fs::create_dir_all(jdk.join("bin")).unwrap();
+        let mut java_build = JavaBuild::new();
+        java_build.java_home(&jdk);
+        let plain = java_build.command().unwrap();
+        java_build.augment_path(true);
+        let augmented = java_build.command().unwrap();
+
+        assert!(plain.get_envs().all(|(key, _)| key != "PATH"));
+        let path = augmented.get_envs().find(|(key, _)| *key == "PATH").and_then(|(_, path)| path).unwrap();
+        assert_eq!(std::env::split_paths(path).next(), Some(jdk.join("bin")));
+    }
+
+    #[test]
+    #[cfg(feature = "cargo")]
+    fn out_dir_in_cargo_out_creates_subdir() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let out_dir = crate::TestDir::new("cargo-out");
+        std::env::remove_var("OUT_DIR");
+        let mut java_build = JavaBuild::new();
+        let unset = java_build.out_dir_in_cargo_out("classes").is_err();
+        std::env::set_var("OUT_DIR", &out_dir);
+        let set = java_build.out_dir_in_cargo_out("classes").is_ok();
+        std::env::remove_var("OUT_DIR");
+
+        assert!(unset && set);
+        assert!(out_dir.join("classes").is_dir());
+        assert_eq!(java_build.classes_out_dir, Some(out_dir.join("classes").into_os_string()));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn compile_roots_reports_every_failed_root() {
+        use std::os::unix::fs::PermissionsExt;
+        let jdk = crate::TestDir::new("jdk-roots");
+        std::fs::create_dir_all(jdk.join("bin")).unwrap();
+        let javac = jdk.join("bin").join("javac");
+        std::fs::write(&javac, "#!/bin/sh\nfor arg; do case $arg in *bad*) exit 1;; esac; done\n").unwrap();
+        std::fs::set_permissions(&javac, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let root = |name: &str| SourceRoot { files: vec![format!("{name}.java").into()], out_dir: name.into() };
+
+        let mut java_build = JavaBuild::new();
+        java_build.java_home(&jdk).max_parallel(2);
+        assert!(java_build.compile_roots(&[root("a"), root("b")]).is_ok());
+        let err = java_build.compile_roots(&[root("bad1"), root("ok"), root("bad2")]).unwrap_err();
+        let lines = err.to_string().lines().map(String::from).collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2, "{err}");
+        assert!(lines[0].contains("bad1") && lines[1].contains("bad2"), "{err}");
+    }
+
+    #[test]
+    fn collect_sources_filtered_applies_predicate() {
+        let dir = crate::TestDir::new("sources");
+        std::fs::create_dir_all(dir.join("main")).unwrap();
+        std::fs::create_dir_all(dir.join("test")).unwrap();
+        for file in ["main/A.java", "main/B.kt", "test/ATest.java"] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+        let mut java_build = JavaBuild::new();
+        java_build.collect_sources_filtered(&dir, |path| !path.starts_with(dir.join("test"))).unwrap();
+        assert_eq!(java_build.files, [dir.join("main/A.java").into_os_string()]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn deserializes_from_plain_strings() {
+        use serde::Deserialize;
+        use serde::de::value::{Error, MapDeserializer};
+        let config = MapDeserializer::<_, Error>::new([
+            ("class_paths", vec!["libs/a.jar", "libs/b.jar"]),
+            ("files", vec!["src/Main.java"]),
+        ].into_iter());
+        let java_build = JavaBuild::deserialize(config).unwrap();
+        assert_eq!(java_build.class_paths, ["libs/a.jar", "libs/b.jar"]);
+        assert_eq!(java_build.files, ["src/Main.java"]);
+        assert!(java_build.release_version.is_none());
+    }
+
+    #[test]
+    fn from_env_uses_jdk_enclosing_java_home() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let jdk = crate::TestDir::new("jdk-from-env");
+        std::fs::create_dir_all(jdk.join("bin")).unwrap();
+        std::fs::create_dir_all(jdk.join("jre")).unwrap();
+        std::fs::write(jdk.join("bin").join(if cfg!(windows) { "javac.exe" } else { "javac" }), b"").unwrap();
+        std::env::remove_var(env_paths::ANDROID_JAVA_HOME);
+        std::env::set_var(env_paths::JAVA_HOME, jdk.join("jre"));
+        let java_build = JavaBuild::from_env();
+        std::env::remove_var(env_paths::JAVA_HOME);
+
+        assert_eq!(java_build.java_home, Some(jdk.to_path_buf()));
+    }
+
+    #[test]
+    fn release_version_with_boot_class_path_is_an_error() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut java_build = JavaBuild::new();
+        let java_home = fake_java_home();
+        java_build.java_home(&*java_home)
+            .release_version("8")
+            .boot_class_path("android.jar");
+        let err = java_build.command().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        java_build.release_version = None;
+        java_build.source_version("8").target_version("8");
+        assert!(java_build.command().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let mut java_build = JavaBuild::new();
+        java_build.java_home("/opt/jdk")
+            .debug_info(DebugInfo { line_numbers: true, variables: false, source_files: true })
+            .implicit(Implicit::Class)
+            .source_version("8")
+            .target_version("8")
+            .class_path("libs/a.jar")
+            .boot_class_path("android.jar")
+            .patch_module("java.base", "patches/base")
+            .classes_out_dir("out/classes")
+            .headers_out_dir("out/headers")
+            .annotation_parameter("key", "value")
+            .classpath_separator(';')
+            .timeout(Duration::from_millis(1500))
+            .file("src/Main.java")
+            .source_list_file("sources.txt");
+        let round_tripped = crate::serde_os::round_trip::round_trip(&java_build);
+        assert_eq!(format!("{:?}", round_tripped), format!("{:?}", java_build));
+    }
 }
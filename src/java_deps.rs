@@ -0,0 +1,147 @@
+//! Builder for customizing and invoking a `jdeps` command.
+
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Output};
+use crate::env_paths::{self, PathExt};
+
+/// A builder for a `jdeps` command that can be invoked
+/// to analyze the dependencies of class files or JAR files.
+///
+/// If you need to customize the `jdeps` command beyond what is provided here,
+/// you can use the [`JavaDeps::command()`] method to get a [`Command`]
+/// that can be further customized with additional arguments.
+///
+/// Documentation on `jdeps` options are based on
+/// <https://dev.java/learn/jvm/tools/core/jdeps/>.
+#[derive(Clone, Debug, Default)]
+pub struct JavaDeps {
+    /// Override the default `JAVA_HOME` path.
+    /// Otherwise, the default path is found using the `JAVA_HOME` env var.
+    java_home: Option<PathBuf>,
+
+    /// Specify where to find class files that the targets depend on.
+    class_paths: Vec<OsString>,
+
+    /// If `true`, recursively traverse all run-time dependencies.
+    recursive: bool,
+
+    /// If `true`, print only a summary of the dependencies.
+    summary: bool,
+
+    /// The class files, directories, or JAR files to be analyzed.
+    targets: Vec<OsString>,
+}
+
+impl JavaDeps {
+    /// Creates a new `JavaDeps` instance with default values,
+    /// which can be further customized using the builder methods.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Executes the `jdeps` command based on this `JavaDeps` instance.
+    pub fn run(&self) -> std::io::Result<ExitStatus> {
+        self.command()?.status()
+    }
+
+    /// Executes the `jdeps` command based on this `JavaDeps` instance,
+    /// capturing its stdout and stderr instead of inheriting them.
+    ///
+    /// The dependency report is available in the `stdout` field of the returned [`Output`].
+    pub fn output(&self) -> std::io::Result<Output> {
+        self.command()?.output()
+    }
+
+    /// Returns a [`Command`] based on this `JavaDeps` instance
+    /// that can be inspected or customized before being executed.
+    pub fn command(&self) -> std::io::Result<Command> {
+        let jh_clone = self.java_home.clone();
+        let java_home = jh_clone
+            .and_then(PathExt::path_if_exists)
+            .or_else(env_paths::java_home_with_javac)
+            .ok_or_else(|| std::io::Error::other(
+                "JAVA_HOME not provided, and no JDK containing `javac` could be auto-discovered."
+            ))?;
+
+        let mut cmd = Command::new(java_home.join("bin").join("jdeps"));
+
+        if !self.class_paths.is_empty() {
+            cmd.arg("-cp").arg(self.class_paths.join(OsStr::new(env_paths::CLASSPATH_SEPARATOR)));
+        }
+        for (flag, cond) in [
+            ("-R", self.recursive),
+            ("-s", self.summary),
+        ].into_iter() {
+            if cond { cmd.arg(flag); }
+        }
+
+        self.targets.iter().for_each(|t| { cmd.arg(t); });
+
+        Ok(cmd)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    //////////////////////// Builder methods below ////////////////////////////
+    ///////////////////////////////////////////////////////////////////////////
+
+    /// Override the default `JAVA_HOME` path.
+    ///
+    /// If not set, the default path is found using the `JAVA_HOME` env var.
+    pub fn java_home<P: Into<PathBuf>>(&mut self, java_home: P) -> &mut Self {
+        self.java_home = Some(java_home.into());
+        self
+    }
+
+    /// Specify where to find class files that the targets depend on.
+    #[doc(alias("-cp", "--class-path"))]
+    pub fn class_path<P: AsRef<OsStr>>(&mut self, class_path: P) -> &mut Self {
+        self.class_paths.push(class_path.as_ref().into());
+        self
+    }
+
+    /// If set to `true`, recursively traverse all run-time dependencies.
+    #[doc(alias("-R", "--recursive"))]
+    pub fn recursive(&mut self, recursive: bool) -> &mut Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// If set to `true`, print only a summary of the dependencies.
+    #[doc(alias("-s", "-summary"))]
+    pub fn summary(&mut self, summary: bool) -> &mut Self {
+        self.summary = summary;
+        self
+    }
+
+    /// Adds a class file, directory, or JAR file to be analyzed.
+    pub fn target<P: AsRef<OsStr>>(&mut self, target: P) -> &mut Self {
+        self.targets.push(target.as_ref().into());
+        self
+    }
+
+    /// Adds multiple class files, directories, or JAR files to be analyzed.
+    ///
+    /// This is the same as calling [`JavaDeps::target()`] multiple times.
+    pub fn targets<I>(&mut self, targets: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<OsStr>,
+    {
+        self.targets.extend(targets.into_iter().map(|t| t.as_ref().into()));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_precede_targets() {
+        let mut jdeps = JavaDeps::new();
+        jdeps.java_home(std::env::temp_dir()).target("app.jar").summary(true).recursive(true).class_path("lib.jar");
+        let args = jdeps.command().unwrap().get_args().map(|arg| arg.to_owned()).collect::<Vec<_>>();
+        assert_eq!(args, ["-cp", "lib.jar", "-R", "-s", "app.jar"]);
+    }
+}
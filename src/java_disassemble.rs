@@ -0,0 +1,166 @@
+//! Builder for customizing and invoking a `javap` command.
+
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Output};
+use crate::env_paths::{self, PathExt};
+
+/// A builder for a `javap` command that can be invoked
+/// to disassemble one or more class files.
+///
+/// If you need to customize the `javap` command beyond what is provided here,
+/// you can use the [`JavaDisassemble::command()`] method to get a [`Command`]
+/// that can be further customized with additional arguments.
+///
+/// Documentation on `javap` options are based on
+/// <https://dev.java/learn/jvm/tools/core/javap/>.
+#[derive(Clone, Debug, Default)]
+pub struct JavaDisassemble {
+    /// Override the default `JAVA_HOME` path.
+    /// Otherwise, the default path is found using the `JAVA_HOME` env var.
+    java_home: Option<PathBuf>,
+
+    /// If `true`, print additional information, e.g., the class file version
+    /// and the constant pool.
+    verbose: bool,
+
+    /// Specify where to find user class files.
+    /// If not provided, the current directory will be used.
+    class_paths: Vec<OsString>,
+
+    /// The classes to disassemble, given as class names,
+    /// paths to class files, or URLs.
+    classes: Vec<OsString>,
+}
+
+impl JavaDisassemble {
+    /// Creates a new `JavaDisassemble` instance with default values,
+    /// which can be further customized using the builder methods.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Executes the `javap` command based on this `JavaDisassemble` instance.
+    pub fn run(&self) -> std::io::Result<ExitStatus> {
+        self.command()?.status()
+    }
+
+    /// Executes the `javap` command based on this `JavaDisassemble` instance,
+    /// capturing its stdout and stderr instead of inheriting them.
+    ///
+    /// The disassembled output is available in the `stdout` field of the returned [`Output`].
+    pub fn output(&self) -> std::io::Result<Output> {
+        self.command()?.output()
+    }
+
+    /// Returns a [`Command`] based on this `JavaDisassemble` instance
+    /// that can be inspected or customized before being executed.
+    pub fn command(&self) -> std::io::Result<Command> {
+        let jh_clone = self.java_home.clone();
+        let java_home = jh_clone
+            .and_then(PathExt::path_if_exists)
+            .or_else(env_paths::java_home_with_javac)
+            .ok_or_else(|| std::io::Error::other(
+                "JAVA_HOME not provided, and no JDK containing `javac` could be auto-discovered."
+            ))?;
+
+        let mut cmd = Command::new(java_home.join("bin").join("javap"));
+
+        if self.verbose {
+            cmd.arg("-v");
+        }
+        if !self.class_paths.is_empty() {
+            cmd.arg("-cp").arg(self.class_paths.join(OsStr::new(env_paths::CLASSPATH_SEPARATOR)));
+        }
+
+        self.classes.iter().for_each(|c| { cmd.arg(c); });
+
+        Ok(cmd)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    //////////////////////// Builder methods below ////////////////////////////
+    ///////////////////////////////////////////////////////////////////////////
+
+    /// Override the default `JAVA_HOME` path.
+    ///
+    /// If not set, the default path is found using the `JAVA_HOME` env var.
+    pub fn java_home<P: Into<PathBuf>>(&mut self, java_home: P) -> &mut Self {
+        self.java_home = Some(java_home.into());
+        self
+    }
+
+    /// If set to `true`, print additional information about each class,
+    /// such as its class file version, constant pool, and stack sizes.
+    #[doc(alias("-v", "-verbose"))]
+    pub fn verbose(&mut self, verbose: bool) -> &mut Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Specify where to find user class files.
+    ///
+    /// If no class paths are provided, the current directory will be used.
+    pub fn class_path<P: AsRef<OsStr>>(&mut self, class_path: P) -> &mut Self {
+        self.class_paths.push(class_path.as_ref().into());
+        self
+    }
+
+    /// Adds a class to be disassembled, given as a fully-qualified class name
+    /// (e.g., `com.example.Main`), a path to a class file, or a URL.
+    pub fn class<S: AsRef<OsStr>>(&mut self, class: S) -> &mut Self {
+        self.classes.push(class.as_ref().into());
+        self
+    }
+
+    /// Adds multiple classes to be disassembled.
+    ///
+    /// This is the same as calling [`JavaDisassemble::class()`] multiple times.
+    pub fn classes<I>(&mut self, classes: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<OsStr>,
+    {
+        self.classes.extend(classes.into_iter().map(|c| c.as_ref().into()));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn class_paths_precede_classes() {
+        let mut javap = JavaDisassemble::new();
+        javap.java_home(std::env::temp_dir()).verbose(true).class("com.example.Main").class_path("classes");
+        let args = javap.command().unwrap().get_args().map(|arg| arg.to_owned()).collect::<Vec<_>>();
+        assert_eq!(args, ["-v", "-cp", "classes", "com.example.Main"]);
+    }
+
+    /// Compiles and disassembles a real class using the JDK found in the environment.
+    #[test]
+    #[cfg(feature = "integration-tests")]
+    fn disassembles_compiled_class() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let dir = crate::TestDir::new("javap");
+        std::fs::create_dir_all(dir.join("classes")).unwrap();
+        std::fs::write(dir.join("Hello.java"), "package rs.robius; public class Hello { public int answer() { return 42; } }").unwrap();
+        crate::JavaBuild::new()
+            .classes_out_dir(dir.join("classes"))
+            .file(dir.join("Hello.java"))
+            .compile_checked()
+            .unwrap();
+
+        let output = JavaDisassemble::new()
+            .class_path(dir.join("classes"))
+            .class("rs.robius.Hello")
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("public class rs.robius.Hello"), "{stdout}");
+        assert!(stdout.contains("public int answer()"), "{stdout}");
+    }
+}
@@ -1,9 +1,11 @@
 //! Builder for customizing and invoking a `java` command.
 
 use std::ffi::{OsStr, OsString};
-use std::path::PathBuf;
-use std::process::{Command, ExitStatus};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::process::{Command, ExitStatus, Output, Stdio};
 use crate::env_paths::{self, PathExt};
+use crate::{JavaBuild, ResolvedCommand};
 
 /// A builder for a `java` command that can be invoked.
 ///
@@ -14,6 +16,8 @@ use crate::env_paths::{self, PathExt};
 /// Documentation on `java` options are based on
 /// <https://dev.java/learn/jvm/tools/core/java/>.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct JavaRun {
     /// Override the default `JAVA_HOME` path.
     /// Otherwise, the default path is found using the `JAVA_HOME` env var.
@@ -21,19 +25,90 @@ pub struct JavaRun {
 
     /// Specify where to find user class files and annotation processors.
     /// If not provided, the current directory will be used.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     class_paths: Vec<OsString>,
 
+    /// If `true`, the entries in the `CLASSPATH` environment variable
+    /// are prepended to `class_paths`.
+    inherit_classpath_env: bool,
+
+    /// If `true`, the options in the `JAVA_OPTS` environment variable
+    /// are passed to the JVM.
+    inherit_java_opts: bool,
+
     /// Specify which main class to run.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::option"))]
     main_class: Option<OsString>,
 
     /// Specify a JAR file to run instead of a main class.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::option"))]
     jar_file: Option<OsString>,
 
+    /// Additional options to be passed to the JVM itself, e.g., `-Xmx1g`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
+    jvm_args: Vec<OsString>,
+
+    /// The charset in which `java` should encode its stdout and stderr.
+    output_encoding: Option<String>,
+
     /// Arguments to be passed to the main class being run by `java`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
     args: Vec<OsString>,
 
     /// If `true`, enable preview language features.
     enable_preview_features: bool,
+
+    /// If `true`, enable assertions in all non-system classes ("-ea" flag).
+    enable_assertions: bool,
+
+    /// Packages or classes for which assertions are specifically enabled.
+    #[doc(alias = "-ea")]
+    enable_assertions_for: Vec<String>,
+
+    /// Packages or classes for which assertions are specifically disabled.
+    #[doc(alias = "-da")]
+    disable_assertions_for: Vec<String>,
+
+    /// Packages to open to other modules for deep reflection,
+    /// as pairs of `<module>/<package>` and the target module(s).
+    #[doc(alias = "--add-opens")]
+    add_opens: Vec<(String, String)>,
+
+    /// Packages to export to other modules,
+    /// as pairs of `<module>/<package>` and the target module(s).
+    #[doc(alias = "--add-exports")]
+    add_exports: Vec<(String, String)>,
+
+    /// Modules that are allowed to perform restricted native operations,
+    /// e.g., via the Foreign Function & Memory API.
+    #[doc(alias = "--enable-native-access")]
+    enable_native_access: Vec<String>,
+
+    /// The thread stack size, e.g., `4m`.
+    #[doc(alias = "-Xss")]
+    stack_size: Option<String>,
+
+    /// The maximum duration that `java` is allowed to run for.
+    timeout: Option<Duration>,
+
+    /// If `true`, print the command instead of executing it.
+    dry_run: bool,
+
+    /// If `true`, skip checking that the JAR file and main class exist.
+    skip_validation: bool,
+
+    /// Data to be written to the stdin of `java`.
+    stdin_data: Option<Vec<u8>>,
+
+    /// A file whose contents are used as the stdin of `java`.
+    stdin_file: Option<PathBuf>,
+
+    /// If `true`, prepend the JDK and Android SDK tool directories to the `PATH`.
+    augment_path: bool,
+
+    /// Override the separator used to join multiple entries in the class path.
+    /// If not provided, the current platform's separator is used.
+    classpath_separator: Option<char>,
 }
 
 impl JavaRun {
@@ -43,13 +118,170 @@ impl JavaRun {
         Default::default()
     }
 
+    /// Creates a new `JavaRun` instance for running classes compiled by the given [`JavaBuild`].
+    ///
+    /// The `classes_out_dir` of the `build` (if any) is added as a class path entry,
+    /// and its `java_home` (if any) is used as the Java home directory.
+    pub fn classpath_from_build(build: &JavaBuild) -> Self {
+        let mut java_run = Self::new();
+        if let Some(java_home) = build.java_home.as_ref() {
+            java_run.java_home(java_home);
+        }
+        if let Some(classes_out_dir) = build.classes_out_dir.as_ref() {
+            java_run.class_path(classes_out_dir);
+        }
+        java_run
+    }
+
     /// Executes the `java` command based on this `JavaRun` instance.
+    ///
+    /// If a [timeout](JavaRun::timeout()) is set and `java` runs for longer than that,
+    /// it is killed and an error of kind [`std::io::ErrorKind::TimedOut`] is returned.
+    ///
+    /// The stdout and stderr of `java` are inherited from the current process.
+    /// To capture them instead, use [`JavaRun::output()`].
     pub fn run(&self) -> std::io::Result<ExitStatus> {
-        self.command()?.status()
+        let mut cmd = self.command()?;
+        if self.dry_run {
+            return Ok(crate::process::dry_run(&cmd));
+        }
+        crate::process::status_with_timeout(&mut cmd, self.timeout, self.stdin_data.as_deref())
+    }
+
+    /// Same as [`JavaRun::run()`], but also returns how long `java` took to run.
+    ///
+    /// This is useful for finding out which steps of a slow build script
+    /// are responsible for most of its run time.
+    pub fn timed_run(&self) -> std::io::Result<(ExitStatus, Duration)> {
+        let start = std::time::Instant::now();
+        let status = self.run()?;
+        Ok((status, start.elapsed()))
+    }
+
+    /// Executes the `java` command based on this `JavaRun` instance,
+    /// returning an error if `java` does not exit successfully.
+    ///
+    /// The error message includes the exit code of `java`.
+    pub fn run_checked(&self) -> std::io::Result<()> {
+        crate::process::check_status("java", self.run()?, None)
+    }
+
+    /// Executes the `java` command based on this `JavaRun` instance,
+    /// capturing its stdout and stderr, and returning an error if `java`
+    /// does not exit successfully.
+    ///
+    /// The error message includes the exit code and the captured stderr of `java`.
+    pub fn output_checked(&self) -> std::io::Result<Output> {
+        let output = self.output()?;
+        crate::process::check_status("java", output.status, Some(&output.stderr))?;
+        Ok(output)
+    }
+
+    /// Executes the `java` command based on this `JavaRun` instance,
+    /// capturing its stdout and stderr instead of inheriting them.
+    ///
+    /// This is the way to capture the output of `java`; [`JavaRun::run()`]
+    /// always lets `java` inherit the stdout and stderr of the current process.
+    ///
+    /// The exit status of the `java` process is available
+    /// in the `status` field of the returned [`Output`].
+    ///
+    /// If [stdin data](JavaRun::stdin_data()) is set, it is written to the stdin of `java`.
+    /// If a [timeout](JavaRun::timeout()) is set and `java` runs for longer than that,
+    /// it is killed and an error of kind [`std::io::ErrorKind::TimedOut`] is returned.
+    ///
+    /// If [`dry_run`](JavaRun::dry_run()) is set, the command is printed instead,
+    /// and the returned output has a successful exit status and is otherwise empty.
+    pub fn output(&self) -> std::io::Result<Output> {
+        let mut cmd = self.command()?;
+        if self.dry_run {
+            return Ok(Output {
+                status: crate::process::dry_run(&cmd),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            });
+        }
+        crate::process::output_with_timeout(&mut cmd, self.timeout, self.stdin_data.as_deref())
+    }
+
+    /// Spawns the `java` command based on this `JavaRun` instance as a detached
+    /// background process that can outlive the current process, e.g., a build daemon.
+    /// Returns the process ID of the spawned `java` process.
+    ///
+    /// The detached process does not inherit stdio: its stdout and stderr are discarded,
+    /// and its stdin is empty unless a [stdin file](JavaRun::stdin_file()) is set.
+    /// [Stdin data](JavaRun::stdin_data()) is not supported and results in an error,
+    /// and no [timeout](JavaRun::timeout()) is applied.
+    ///
+    /// Platform differences:
+    /// * On Unix-like systems, the process is placed in its own process group,
+    ///   such that it does not receive signals (e.g., Ctrl+C) sent to the group
+    ///   of the current process. It is not placed in a new session.
+    /// * On Windows, the process is created with `DETACHED_PROCESS` and
+    ///   `CREATE_NEW_PROCESS_GROUP`, such that it has no console.
+    ///
+    /// If [`dry_run`](JavaRun::dry_run()) is set, the command is printed instead
+    /// and a process ID of `0` is returned.
+    pub fn detach(&self) -> std::io::Result<u32> {
+        if self.stdin_data.is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Cannot provide stdin data to a detached process.",
+            ));
+        }
+        let mut cmd = self.command()?;
+        if self.dry_run {
+            crate::process::dry_run(&cmd);
+            return Ok(0);
+        }
+        if self.stdin_file.is_none() {
+            cmd.stdin(Stdio::null());
+        }
+        cmd.stdout(Stdio::null()).stderr(Stdio::null());
+        #[cfg(unix)] {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        #[cfg(windows)] {
+            use std::os::windows::process::CommandExt;
+            const DETACHED_PROCESS: u32 = 0x0000_0008;
+            const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+            cmd.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+        }
+        Ok(cmd.spawn()?.id())
+    }
+
+    /// Returns a human-readable string of the `java` command
+    /// that would be executed based on this `JavaRun` instance.
+    ///
+    /// This is intended for display and debugging purposes only,
+    /// not for execution by a shell.
+    pub fn command_string(&self) -> std::io::Result<String> {
+        Ok(crate::process::command_string(&self.command()?))
+    }
+
+    /// Returns the fully-resolved `java` command as plain data,
+    /// i.e., the same program and arguments as [`JavaRun::command()`].
+    ///
+    /// Unlike a [`Command`], a [`ResolvedCommand`] can be inspected, compared,
+    /// and serialized, e.g., to be handed off to a task runner.
+    pub fn to_owned_command(&self) -> std::io::Result<ResolvedCommand> {
+        Ok(ResolvedCommand::from(&self.command()?))
     }
 
     /// Returns a [`Command`] based on this `JavaRun` instance
     /// that can be inspected or customized before being executed.
+    ///
+    /// The arguments are always emitted in the following order,
+    /// as anything after the main class or `-jar <jar>` is passed to the program
+    /// rather than the JVM:
+    /// 1. JVM options, with those added via [`JavaRun::jvm_arg()`] last.
+    /// 2. The class path (`-cp`).
+    /// 3. The main class, or `-jar <jar>`.
+    /// 4. Program arguments added via [`JavaRun::arg()`].
+    ///
+    /// Unless [`JavaRun::skip_validation()`] is set, this returns an error
+    /// if the JAR file or main class cannot be found.
     pub fn command(&self) -> std::io::Result<Command> {
         let jh_clone = self.java_home.clone();
         let java_home = jh_clone
@@ -60,12 +292,41 @@ impl JavaRun {
             ))?;
 
         let mut cmd = Command::new(java_home.join("bin").join("java"));
+        if self.augment_path {
+            if let Some(path) = env_paths::augmented_path(&java_home) {
+                cmd.env("PATH", path);
+            }
+        }
 
         if self.enable_preview_features {
             cmd.arg("--enable-preview");
         }
-        if !self.class_paths.is_empty() {
-            cmd.arg("-cp").arg(self.class_paths.join(OsStr::new(";")));
+        if self.enable_assertions {
+            cmd.arg("-ea");
+        }
+        self.enable_assertions_for .iter().for_each(|s| { cmd.arg(format!("-ea:{}", s)); });
+        self.disable_assertions_for.iter().for_each(|s| { cmd.arg(format!("-da:{}", s)); });
+        self.add_opens  .iter().for_each(|(p, t)| { cmd.arg("--add-opens").arg(format!("{}={}", p, t)); });
+        self.add_exports.iter().for_each(|(p, t)| { cmd.arg("--add-exports").arg(format!("{}={}", p, t)); });
+        if !self.enable_native_access.is_empty() {
+            cmd.arg(format!("--enable-native-access={}", self.enable_native_access.join(",")));
+        }
+        if let Some(stack_size) = self.stack_size.as_deref() {
+            cmd.arg(format!("-Xss{}", stack_size));
+        }
+        if let Some(charset) = self.output_encoding.as_deref() {
+            cmd.args(crate::process::output_encoding_properties(charset));
+        }
+        if self.inherit_java_opts {
+            cmd.args(env_paths::java_opts_env());
+        }
+        self.jvm_args.iter().for_each(|a| { cmd.arg(a); });
+        let class_paths = self.effective_class_paths();
+        if !self.skip_validation {
+            self.validate(&class_paths)?;
+        }
+        if !class_paths.is_empty() {
+            cmd.arg("-cp").arg(class_paths.join(OsStr::new(&self.effective_classpath_separator())));
         }
         match (self.main_class.as_ref(), self.jar_file.as_ref()) {
             (Some(main_class), None) => { cmd.arg(main_class); }
@@ -81,9 +342,81 @@ impl JavaRun {
 
         self.args.iter().for_each(|f| { cmd.arg(f); });
 
+        match (self.stdin_data.as_ref(), self.stdin_file.as_ref()) {
+            (Some(_), None) => { cmd.stdin(Stdio::piped()); }
+            (None, Some(stdin_file)) => { cmd.stdin(std::fs::File::open(stdin_file)?); }
+            (Some(_), Some(_)) => {
+                return Err(std::io::Error::other(
+                    "Cannot provide both stdin data AND a stdin file."
+                ));
+            },
+            _ => { }
+        }
+
         Ok(cmd)
     }
 
+    /// Checks that the JAR file exists and that the main class is a dotted class name.
+    ///
+    /// The main class is not required to be found in the class path directories,
+    /// as it may come from the JDK or a module, or be compiled later.
+    fn validate(&self, class_paths: &[OsString]) -> std::io::Result<()> {
+        if let Some(jar_file) = self.jar_file.as_ref() {
+            if !Path::new(jar_file).is_file() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("The JAR file {:?} does not exist.", jar_file),
+                ));
+            }
+        }
+        if let Some(main_class) = self.main_class.as_ref().and_then(|c| c.to_str()) {
+            let dotted = main_class
+                .strip_suffix(".class")
+                .or_else(|| main_class.strip_suffix(".java"))
+                .unwrap_or(main_class)
+                .replace(['/', '\\'], ".");
+            if dotted != main_class {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("The main class {:?} must be a dotted class name like {:?}, \
+                        not a file path or internal name.",
+                        main_class, dotted,
+                    ),
+                ));
+            }
+            let class_dirs = class_paths.iter().map(Path::new).collect::<Vec<_>>();
+            let class_file = format!("{}.class", main_class.replace('.', "/"));
+            if !class_dirs.is_empty()
+                && class_dirs.iter().all(|dir| dir.is_dir())
+                && !class_dirs.iter().any(|dir| dir.join(&class_file).is_file())
+            {
+                log_debug!("The main class {:?} was not found in any class path directory: {:?}",
+                    main_class, class_paths,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the class paths to be used, including those from
+    /// the `CLASSPATH` environment variable if requested.
+    fn effective_class_paths(&self) -> Vec<OsString> {
+        let mut class_paths = if self.inherit_classpath_env {
+            env_paths::classpath_env()
+        } else {
+            Vec::new()
+        };
+        class_paths.extend(self.class_paths.iter().cloned());
+        class_paths
+    }
+
+    /// Returns the separator used to join multiple entries in the class path.
+    fn effective_classpath_separator(&self) -> String {
+        self.classpath_separator
+            .map(String::from)
+            .unwrap_or_else(|| env_paths::CLASSPATH_SEPARATOR.into())
+    }
+
     ///////////////////////////////////////////////////////////////////////////
     //////////////////////// Builder methods below ////////////////////////////
     ///////////////////////////////////////////////////////////////////////////
@@ -104,12 +437,185 @@ impl JavaRun {
         self
     }
 
+    /// Adds multiple class path entries at once.
+    ///
+    /// This is the same as calling [`JavaRun::class_path()`] multiple times.
+    pub fn class_paths<P>(&mut self, class_paths: P) -> &mut Self
+    where
+        P: IntoIterator,
+        P::Item: AsRef<OsStr>,
+    {
+        self.class_paths.extend(class_paths.into_iter().map(|p| p.as_ref().into()));
+        self
+    }
+
+    /// Override the separator used to join multiple class path entries.
+    ///
+    /// By default, the current platform's separator is used: `;` on Windows, `:` elsewhere.
+    /// Overriding this is only necessary when generating a command that will be run
+    /// on a different platform than the current one, e.g., inside WSL targeting
+    /// a Windows `java.exe` with Windows-style paths.
+    pub fn classpath_separator(&mut self, separator: char) -> &mut Self {
+        self.classpath_separator = Some(separator);
+        self
+    }
+
+    /// If set to `true`, the entries in the `CLASSPATH` environment variable
+    /// will be prepended to the class paths given via [`JavaRun::class_path()`].
+    ///
+    /// This is disabled by default, in which case `java` itself only consults
+    /// `CLASSPATH` if no class paths were provided at all.
+    pub fn inherit_classpath_env(&mut self, inherit_classpath_env: bool) -> &mut Self {
+        self.inherit_classpath_env = inherit_classpath_env;
+        self
+    }
+
+    /// If set to `true`, the options in the `JAVA_OPTS` environment variable
+    /// are passed to the JVM, before those given via [`JavaRun::jvm_arg()`].
+    ///
+    /// Options are split on whitespace, with `'` and `"` quoting supported,
+    /// but no other shell features. Note that this passes arbitrary options
+    /// from the environment to `java`, so only enable this if the environment is trusted.
+    ///
+    /// The `_JAVA_OPTIONS` and `JAVA_TOOL_OPTIONS` environment variables
+    /// are already honored by the JVM itself, so they are not handled here.
+    pub fn inherit_java_opts(&mut self, inherit_java_opts: bool) -> &mut Self {
+        self.inherit_java_opts = inherit_java_opts;
+        self
+    }
+
     /// Enable or disable preview language features.
     pub fn enable_preview_features(&mut self, enable_preview_features: bool) -> &mut Self {
         self.enable_preview_features = enable_preview_features;
         self
     }
-    
+
+    /// If set to `true`, enable assertions in all non-system classes.
+    #[doc(alias("-ea", "enableassertions"))]
+    pub fn enable_assertions(&mut self, enable_assertions: bool) -> &mut Self {
+        self.enable_assertions = enable_assertions;
+        self
+    }
+
+    /// Enable assertions only for the given package or class.
+    ///
+    /// The `spec` is either a fully-qualified class name (e.g., `com.example.Main`)
+    /// or a package name followed by `...` (e.g., `com.example...`),
+    /// which also includes all of its subpackages.
+    #[doc(alias("-ea:", "enableassertions"))]
+    pub fn enable_assertions_for<S: Into<String>>(&mut self, spec: S) -> &mut Self {
+        self.enable_assertions_for.push(spec.into());
+        self
+    }
+
+    /// Disable assertions for the given package or class.
+    ///
+    /// The `spec` follows the same format as [`JavaRun::enable_assertions_for()`].
+    /// These are emitted after all enabled assertion specs, so they take precedence.
+    #[doc(alias("-da:", "disableassertions"))]
+    pub fn disable_assertions_for<S: Into<String>>(&mut self, spec: S) -> &mut Self {
+        self.disable_assertions_for.push(spec.into());
+        self
+    }
+
+    /// Open a package of a module to the target module(s) at runtime,
+    /// allowing deep reflective access to its non-public members.
+    ///
+    /// * `module_pkg` is of the form `<module>/<package>`, e.g., `java.base/java.lang`.
+    /// * `target` is a comma-separated list of module names, or `ALL-UNNAMED`.
+    ///
+    /// This can be called multiple times to open multiple packages.
+    #[doc(alias("--add-opens"))]
+    pub fn add_opens<P, T>(&mut self, module_pkg: P, target: T) -> &mut Self
+    where
+        P: Into<String>,
+        T: Into<String>,
+    {
+        self.add_opens.push((module_pkg.into(), target.into()));
+        self
+    }
+
+    /// Export a package of a module to the target module(s) at runtime,
+    /// allowing access to its public members.
+    ///
+    /// * `module_pkg` is of the form `<module>/<package>`, e.g., `jdk.compiler/com.sun.tools.javac.api`.
+    /// * `target` is a comma-separated list of module names, or `ALL-UNNAMED`.
+    ///
+    /// This can be called multiple times to export multiple packages.
+    #[doc(alias("--add-exports"))]
+    pub fn add_exports<P, T>(&mut self, module_pkg: P, target: T) -> &mut Self
+    where
+        P: Into<String>,
+        T: Into<String>,
+    {
+        self.add_exports.push((module_pkg.into(), target.into()));
+        self
+    }
+
+    /// Allow the given module to perform restricted native operations,
+    /// such as those of the Foreign Function & Memory API (JDK 21+),
+    /// without a warning.
+    ///
+    /// The `module` is a module name, or `ALL-UNNAMED` for all code on the class path.
+    /// This can be called multiple times; all modules are combined into a single option.
+    #[doc(alias("--enable-native-access"))]
+    pub fn enable_native_access<S: Into<String>>(&mut self, module: S) -> &mut Self {
+        self.enable_native_access.push(module.into());
+        self
+    }
+
+    /// Set the thread stack size, e.g., `4m` or `512k`,
+    /// which is useful for tools that recurse deeply.
+    #[doc(alias("-Xss"))]
+    pub fn stack_size<S: Into<String>>(&mut self, size: S) -> &mut Self {
+        self.stack_size = Some(size.into());
+        self
+    }
+
+    /// If set to `true`, the `PATH` of the `java` process is the current `PATH`
+    /// with the following directories prepended to it, if they exist:
+    /// * the `bin` directory of the JDK in use,
+    /// * the Android SDK's `platform-tools` directory, and
+    /// * the Android SDK's `build-tools` directory given by [`build_tools_dir()`](crate::build_tools_dir).
+    ///
+    /// This is useful when `java` or code it runs (e.g., annotation processors)
+    /// invokes other tools by name.
+    pub fn augment_path(&mut self, augment_path: bool) -> &mut Self {
+        self.augment_path = augment_path;
+        self
+    }
+
+    /// Provide data to be written to the stdin of `java` when it is run
+    /// via [`JavaRun::run()`] or [`JavaRun::output()`].
+    ///
+    /// Only one stdin source may be set; setting both this and
+    /// [`JavaRun::stdin_file()`] causes [`JavaRun::command()`] to return an error.
+    /// If neither is set, stdin is inherited by [`JavaRun::run()`].
+    pub fn stdin_data<D: Into<Vec<u8>>>(&mut self, stdin_data: D) -> &mut Self {
+        self.stdin_data = Some(stdin_data.into());
+        self
+    }
+
+    /// Use the contents of the given file as the stdin of `java`.
+    ///
+    /// Only one stdin source may be set; setting both this and
+    /// [`JavaRun::stdin_data()`] causes [`JavaRun::command()`] to return an error.
+    /// The file is opened by [`JavaRun::command()`].
+    pub fn stdin_file<P: AsRef<Path>>(&mut self, stdin_file: P) -> &mut Self {
+        self.stdin_file = Some(stdin_file.as_ref().into());
+        self
+    }
+
+    /// If set to `true`, skip the validation performed in [`JavaRun::command()`],
+    /// which otherwise checks that:
+    /// * the JAR file exists, if one was given, and
+    /// * the main class is a dotted class name like `com.example.Main`, rather than
+    ///   an internal name like `com/example/Main` or a file name like `Main.class`.
+    pub fn skip_validation(&mut self, skip_validation: bool) -> &mut Self {
+        self.skip_validation = skip_validation;
+        self
+    }
+
     /// Specify the main class to launch when running the `java` command.
     ///
     /// Note that this and the `jar_file` are mutually exclusive;
@@ -128,6 +634,56 @@ impl JavaRun {
         self
     }
 
+    /// If set to `true`, [`JavaRun::run()`] and [`JavaRun::output()`] print the `java` command
+    /// that would be run instead of executing it, and return a successful exit status.
+    ///
+    /// The command is printed as a Cargo warning if the `cargo` feature is enabled,
+    /// or to stderr otherwise.
+    pub fn dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Set the maximum duration that `java` is allowed to run for
+    /// when invoked via [`JavaRun::run()`] or [`JavaRun::output()`].
+    ///
+    /// If not set, `java` is allowed to run indefinitely.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the charset in which `java` encodes its stdout and stderr, e.g., `UTF-8`.
+    ///
+    /// This is useful when capturing output via [`JavaRun::output()`] on Windows,
+    /// where the JVM otherwise uses the console's code page, which results in
+    /// garbled text when the output is decoded as UTF-8.
+    /// If not set, the JVM's default encoding is used.
+    pub fn output_encoding<S: Into<String>>(&mut self, charset: S) -> &mut Self {
+        self.output_encoding = Some(charset.into());
+        self
+    }
+
+    /// Add an option to be passed to the JVM itself, e.g., `-Xmx1g` or `-Dkey=value`.
+    ///
+    /// Unlike [`JavaRun::arg()`], these are placed *before* the main class or JAR file.
+    pub fn jvm_arg<S: AsRef<OsStr>>(&mut self, jvm_arg: S) -> &mut Self {
+        self.jvm_args.push(jvm_arg.as_ref().into());
+        self
+    }
+
+    /// Adds multiple options to be passed to the JVM itself.
+    ///
+    /// This is the same as calling [`JavaRun::jvm_arg()`] multiple times.
+    pub fn jvm_args<I>(&mut self, jvm_args: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<OsStr>,
+    {
+        self.jvm_args.extend(jvm_args.into_iter().map(|a| a.as_ref().into()));
+        self
+    }
+
     /// Add an argument to be passed to the main class being run by `java`.
     pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Self {
         self.args.push(arg.as_ref().into());
@@ -143,4 +699,244 @@ impl JavaRun {
         self.args.extend(args.into_iter().map(|a| a.as_ref().into()));
         self
     }
+
+    /// Resets this `JavaRun` to its default configuration,
+    /// as if it were newly created with [`JavaRun::new()`].
+    ///
+    /// This is useful when reusing a single `JavaRun` across loop iterations.
+    pub fn reset(&mut self) -> &mut Self {
+        *self = Self::default();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the arguments of the `java` command built by the given `java_run`,
+    /// using an existing directory as the Java home unless one was set.
+    fn args(java_run: &mut JavaRun) -> Vec<String> {
+        if java_run.java_home.is_none() {
+            java_run.java_home(std::env::temp_dir());
+        }
+        java_run.command().unwrap()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn inherit_classpath_env_prepends_classpath_entries() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        std::env::set_var(env_paths::CLASSPATH, std::env::join_paths(["/env/a", "/env/b"]).unwrap());
+
+        let mut java_run = JavaRun::new();
+        java_run.class_path("/explicit");
+        let without_env = java_run.effective_class_paths();
+        java_run.inherit_classpath_env(true);
+        let with_env = java_run.effective_class_paths();
+        std::env::remove_var(env_paths::CLASSPATH);
+
+        assert_eq!(without_env, ["/explicit"]);
+        assert_eq!(with_env, ["/env/a", "/env/b", "/explicit"]);
+    }
+
+    #[test]
+    fn empty_classpath_entries_are_ignored() {
+        let _env = crate::ENV_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        std::env::set_var(env_paths::CLASSPATH, std::env::join_paths(["", "/env/a", ""]).unwrap());
+        let class_paths = env_paths::classpath_env();
+        std::env::remove_var(env_paths::CLASSPATH);
+
+        assert_eq!(class_paths, ["/env/a"]);
+    }
+
+    #[test]
+    fn missing_jar_file_is_an_error() {
+        let mut java_run = JavaRun::new();
+        java_run.jar_file("/nonexistent/app.jar");
+        let err = java_run.validate(&[]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        assert!(err.to_string().contains("app.jar"));
+    }
+
+    #[test]
+    fn main_class_outside_class_path_dirs_is_allowed() {
+        let class_dir = std::env::temp_dir();
+        let mut java_run = JavaRun::new();
+        java_run.main_class("com.example.NotCompiledYet");
+        assert!(java_run.validate(&[class_dir.into_os_string()]).is_ok());
+    }
+
+    #[test]
+    fn slashed_or_file_name_main_class_is_an_error() {
+        for (main_class, suggestion) in [
+            ("com/example/Main", "com.example.Main"),
+            ("com.example.Main.class", "com.example.Main"),
+            ("Main.java", "Main"),
+        ] {
+            let mut java_run = JavaRun::new();
+            java_run.main_class(main_class);
+            let err = java_run.validate(&[]).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+            assert!(err.to_string().contains(&format!("{suggestion:?}")), "{err}");
+        }
+    }
+
+    #[test]
+    fn skip_validation_allows_slashed_main_class() {
+        let mut java_run = JavaRun::new();
+        java_run.java_home(std::env::temp_dir()).main_class("com/example/Main");
+        assert!(java_run.command().is_err());
+        java_run.skip_validation(true);
+        assert!(java_run.command().is_ok());
+    }
+
+    #[test]
+    fn disabled_assertions_follow_enabled_ones() {
+        let mut java_run = JavaRun::new();
+        java_run
+            .disable_assertions_for("com.example.Slow")
+            .enable_assertions(true)
+            .enable_assertions_for("com.example...")
+            .main_class("Main");
+        assert_eq!(args(&mut java_run), ["-ea", "-ea:com.example...", "-da:com.example.Slow", "Main"]);
+    }
+
+    #[test]
+    fn add_opens_and_exports() {
+        let mut java_run = JavaRun::new();
+        java_run
+            .add_opens("java.base/java.lang", "ALL-UNNAMED")
+            .add_exports("jdk.compiler/com.sun.tools.javac.api", "com.example");
+        assert_eq!(args(&mut java_run), [
+            "--add-opens", "java.base/java.lang=ALL-UNNAMED",
+            "--add-exports", "jdk.compiler/com.sun.tools.javac.api=com.example",
+        ]);
+    }
+
+    #[test]
+    fn program_args_follow_the_jar_file() {
+        let dir = crate::TestDir::new("jar");
+        let jar = dir.join("app.jar");
+        std::fs::write(&jar, b"").unwrap();
+        let mut java_run = JavaRun::new();
+        java_run.arg("--input").jvm_arg("-Xmx1g").jar_file(&jar).arg("in.txt");
+        assert_eq!(args(&mut java_run), ["-Xmx1g", "-jar", &jar.to_string_lossy(), "--input", "in.txt"]);
+    }
+
+    #[test]
+    fn native_access_and_stack_size_precede_jvm_args() {
+        let mut java_run = JavaRun::new();
+        java_run
+            .jvm_arg("-Xmx1g")
+            .enable_native_access("ALL-UNNAMED")
+            .enable_native_access("com.example")
+            .stack_size("4m");
+        assert_eq!(args(&mut java_run), ["--enable-native-access=ALL-UNNAMED,com.example", "-Xss4m", "-Xmx1g"]);
+    }
+
+    #[test]
+    fn stdin_data_and_file_are_exclusive() {
+        let mut java_run = JavaRun::new();
+        java_run.java_home(std::env::temp_dir()).stdin_data("input").stdin_file("/nonexistent/input.txt");
+        let err = java_run.command().unwrap_err();
+        assert!(err.to_string().contains("stdin"), "{err}");
+    }
+
+    #[test]
+    fn classpath_from_build_uses_its_out_dir_and_java_home() {
+        let mut build = JavaBuild::new();
+        build.java_home("/jdk").classes_out_dir("/build/classes");
+        let java_run = JavaRun::classpath_from_build(&build);
+        assert_eq!(java_run.java_home.as_deref(), Some(Path::new("/jdk")));
+        assert_eq!(java_run.class_paths, ["/build/classes"]);
+
+        let java_run = JavaRun::classpath_from_build(&JavaBuild::new());
+        assert!(java_run.java_home.is_none() && java_run.class_paths.is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn output_captures_stdout() {
+        use std::os::unix::fs::PermissionsExt;
+        let java_home = crate::TestDir::new("fake-java");
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        let java = java_home.join("bin").join("java");
+        std::fs::write(&java, "#!/bin/sh\necho \"$@\"\n").unwrap();
+        std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let output = JavaRun::new().java_home(&java_home).main_class("Main").arg("hi").output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"Main hi\n");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn timed_run_measures_the_run() {
+        let java_home = crate::TestDir::new("slow-java");
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        std::os::unix::fs::symlink("/bin/sleep", java_home.join("bin").join("java")).unwrap();
+        let (status, duration) = JavaRun::new().java_home(&java_home).arg("0.2").timed_run().unwrap();
+        assert!(status.success());
+        assert!(duration >= Duration::from_millis(200), "{duration:?}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn detach_spawns_without_waiting() {
+        let java_home = crate::TestDir::new("daemon-java");
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        std::os::unix::fs::symlink("/bin/sleep", java_home.join("bin").join("java")).unwrap();
+        let mut java_run = JavaRun::new();
+        java_run.java_home(&java_home).arg("5");
+
+        let start = std::time::Instant::now();
+        let pid = java_run.detach().unwrap();
+        assert!(pid != 0 && start.elapsed() < Duration::from_secs(5));
+        let _ = std::process::Command::new("kill").arg(pid.to_string()).status();
+
+        assert_eq!(java_run.clone().dry_run(true).detach().unwrap(), 0);
+        let err = java_run.stdin_data("input").detach().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn dry_run_output_does_not_start_java() {
+        use std::os::unix::fs::PermissionsExt;
+        let java_home = crate::TestDir::new("dry-run-java");
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        let marker = java_home.join("started");
+        let java = java_home.join("bin").join("java");
+        std::fs::write(&java, format!("#!/bin/sh\ntouch '{}'\n", marker.display())).unwrap();
+        std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut java_run = JavaRun::new();
+        java_run.java_home(&java_home).main_class("Main").dry_run(true);
+        let output = java_run.output().unwrap();
+        assert!(output.status.success() && output.stdout.is_empty());
+        assert!(java_run.output_checked().is_ok());
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let mut java_run = JavaRun::new();
+        java_run.java_home("/opt/jdk")
+            .class_path("libs/a.jar")
+            .main_class("com.example.Main")
+            .jvm_arg("-Xmx1g")
+            .arg("--input")
+            .arg("in.txt")
+            .add_opens("java.base/java.lang", "ALL-UNNAMED")
+            .stack_size("4m")
+            .stdin_data(b"hello".to_vec())
+            .stdin_file("input.txt")
+            .timeout(std::time::Duration::from_secs(5));
+        let round_tripped = crate::serde_os::round_trip::round_trip(&java_run);
+        assert_eq!(format!("{:?}", round_tripped), format!("{:?}", java_run));
+    }
 }
@@ -0,0 +1,175 @@
+//! Builder for customizing and invoking a `javadoc` command.
+
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Output};
+use crate::env_paths::{self, PathExt};
+
+/// A builder for a `javadoc` command that can be invoked
+/// to generate API documentation from Java source files.
+///
+/// If you need to customize the `javadoc` command beyond what is provided here,
+/// you can use the [`Javadoc::command()`] method to get a [`Command`]
+/// that can be further customized with additional arguments.
+///
+/// Documentation on `javadoc` options are based on
+/// <https://dev.java/learn/jvm/tools/core/javadoc/>.
+#[derive(Clone, Debug, Default)]
+pub struct Javadoc {
+    /// Override the default `JAVA_HOME` path.
+    /// Otherwise, the default path is found using the `JAVA_HOME` env var.
+    java_home: Option<PathBuf>,
+
+    /// Specify where to find input source files.
+    source_paths: Vec<OsString>,
+
+    /// Specify where to find user class files referenced by the source files.
+    class_paths: Vec<OsString>,
+
+    /// Specify where to place the generated documentation.
+    out_dir: Option<OsString>,
+
+    /// Whether to enable (`true`) or disable (`false`) doclint checks.
+    /// If not provided, `javadoc`'s default behavior is used.
+    doclint: Option<bool>,
+
+    /// Paths to the Java source files to be documented.
+    files: Vec<OsString>,
+}
+
+impl Javadoc {
+    /// Creates a new `Javadoc` instance with default values,
+    /// which can be further customized using the builder methods.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Executes the `javadoc` command based on this `Javadoc` instance.
+    pub fn run(&self) -> std::io::Result<ExitStatus> {
+        self.command()?.status()
+    }
+
+    /// Executes the `javadoc` command based on this `Javadoc` instance,
+    /// capturing its stdout and stderr instead of inheriting them.
+    pub fn output(&self) -> std::io::Result<Output> {
+        self.command()?.output()
+    }
+
+    /// Returns a [`Command`] based on this `Javadoc` instance
+    /// that can be inspected or customized before being executed.
+    pub fn command(&self) -> std::io::Result<Command> {
+        let jh_clone = self.java_home.clone();
+        let java_home = jh_clone
+            .and_then(PathExt::path_if_exists)
+            .or_else(env_paths::java_home_with_javac)
+            .ok_or_else(|| std::io::Error::other(
+                "JAVA_HOME not provided, and no JDK containing `javadoc` could be auto-discovered."
+            ))?;
+
+        let mut cmd = Command::new(java_home.join("bin").join("javadoc"));
+
+        for (flag, paths) in [
+            ("-sourcepath", &self.source_paths),
+            ("-cp",         &self.class_paths),
+        ] {
+            if !paths.is_empty() {
+                cmd.arg(flag).arg(paths.join(OsStr::new(env_paths::CLASSPATH_SEPARATOR)));
+            }
+        }
+        if let Some(out_dir) = self.out_dir.as_ref() {
+            cmd.arg("-d").arg(out_dir);
+        }
+        match self.doclint {
+            Some(true) => { cmd.arg("-Xdoclint"); }
+            Some(false) => { cmd.arg("-Xdoclint:none"); }
+            None => { }
+        }
+
+        self.files.iter().for_each(|f| { cmd.arg(f); });
+
+        Ok(cmd)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    //////////////////////// Builder methods below ////////////////////////////
+    ///////////////////////////////////////////////////////////////////////////
+
+    /// Override the default `JAVA_HOME` path.
+    ///
+    /// If not set, the default path is found using the `JAVA_HOME` env var.
+    pub fn java_home<P: Into<PathBuf>>(&mut self, java_home: P) -> &mut Self {
+        self.java_home = Some(java_home.into());
+        self
+    }
+
+    /// Specify where to find input source files.
+    #[doc(alias("-sourcepath", "--source-path"))]
+    pub fn source_path<P: AsRef<OsStr>>(&mut self, source_path: P) -> &mut Self {
+        self.source_paths.push(source_path.as_ref().into());
+        self
+    }
+
+    /// Specify where to find user class files referenced by the source files.
+    #[doc(alias("-cp", "--class-path"))]
+    pub fn class_path<P: AsRef<OsStr>>(&mut self, class_path: P) -> &mut Self {
+        self.class_paths.push(class_path.as_ref().into());
+        self
+    }
+
+    /// Specify where to place the generated documentation.
+    ///
+    /// If not provided, the documentation is placed in the current directory.
+    #[doc(alias("-d"))]
+    pub fn out_dir<P: AsRef<OsStr>>(&mut self, out_dir: P) -> &mut Self {
+        self.out_dir = Some(out_dir.as_ref().into());
+        self
+    }
+
+    /// If set to `false`, disable all doclint checks of documentation comments
+    /// ("-Xdoclint:none"), which otherwise cause errors for malformed comments.
+    ///
+    /// If set to `true`, enable all recommended doclint checks ("-Xdoclint").
+    #[doc(alias("-Xdoclint"))]
+    pub fn doclint(&mut self, doclint: bool) -> &mut Self {
+        self.doclint = Some(doclint);
+        self
+    }
+
+    /// Adds a Java source file to be documented.
+    pub fn file<P: AsRef<OsStr>>(&mut self, file: P) -> &mut Self {
+        self.files.push(file.as_ref().into());
+        self
+    }
+
+    /// Adds multiple Java source files to be documented.
+    ///
+    /// This is the same as calling [`Javadoc::file()`] multiple times.
+    pub fn files<I>(&mut self, files: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<OsStr>,
+    {
+        self.files.extend(files.into_iter().map(|f| f.as_ref().into()));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(javadoc: &Javadoc) -> Vec<OsString> {
+        javadoc.command().unwrap().get_args().map(|arg| arg.to_owned()).collect()
+    }
+
+    #[test]
+    fn doclint_flags() {
+        let mut javadoc = Javadoc::new();
+        javadoc.java_home(std::env::temp_dir()).out_dir("docs").file("A.java");
+        assert_eq!(args(&javadoc), ["-d", "docs", "A.java"]);
+        javadoc.doclint(true);
+        assert_eq!(args(&javadoc), ["-d", "docs", "-Xdoclint", "A.java"]);
+        javadoc.doclint(false);
+        assert_eq!(args(&javadoc), ["-d", "docs", "-Xdoclint:none", "A.java"]);
+    }
+}
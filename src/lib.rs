@@ -3,13 +3,21 @@
 //! ## Tools exposed by this crate
 //! * javac: use the [`JavaBuild`] struct.
 //! * java: use the [`JavaRun`] struct.
+//! * javap: use the [`JavaDisassemble`] struct.
+//! * jar: use the [`JarBuild`] struct.
+//! * jdeps: use the [`JavaDeps`] struct.
+//! * javadoc: use the [`Javadoc`] struct.
+//! * dexdump: use the [`DexDump`] struct.
+//! * aapt2, d8, zipalign, and apksigner, to package an APK: use the [`AndroidApk`] struct.
 // //! * d8: through the [`Dexer`] struct.
 //!
 //! ## Environment variables in use
 //! * `ANDROID_HOME` or `ANDROID_SDK_ROOT`: path to the Android SDK directory.
 //! * `ANDROID_BUILD_TOOLS_VERSION`: the version of the Android build tools.
 //!   * Examples: `33.0.1`, `34.0.0-rc2`.
-//!   * This must be fully specified all in one string.
+//!   * A partial version like `34` or `34.0` selects the latest installed
+//!     build tools with that prefix, e.g., `34.0.1`; an exact match is always preferred.
+//!   * If not set, the latest installed version of the build tools is used.
 //! * `ANDROID_PLATFORM`, `ANDROID_API_LEVEL`, or `ANDROID_SDK_VERSION`:
 //!   the platform version string (aka API level, SDK version) being targeted for compilation.
 //!   * All three of these environment variables are treated identically.
@@ -18,6 +26,9 @@
 //!     like so: `android-33-ext4`.
 //!   * This may or may not include the SDK extension level as a suffix
 //!     (see `ANDROID_SDK_EXTENSION` below).
+//!   * If none of these are set, the Gradle-style aliases `ANDROID_COMPILE_SDK`
+//!     and then `COMPILE_SDK_VERSION` are used instead.
+//!   * If none of these are set, the latest installed platform is used.
 //! * `ANDROID_SDK_EXTENSION`: the extension of the Android SDK.
 //!   * To specify `android-33-ext4`, this can be set to `-ext4`, `ext4`, or just `4`.
 //!     All of these will be treated identically.
@@ -25,11 +36,47 @@
 //!     already includes an extension, then `ANDROID_SDK_EXTENSION` will be ignored.
 //! * `ANDROID_D8_JAR`: the path to the `d8.jar` file.
 //! * `ANDROID_JAR`: the path to the `android.jar` file.
+//! * `ANDROID_AAPT2`, `ANDROID_ZIPALIGN`, `ANDROID_APKSIGNER`: the paths to the
+//!   `aapt2`, `zipalign`, and `apksigner` tools, overriding discovery in the build tools directory.
+//! * `ADB`: the path to the `adb` tool, overriding discovery in the platform tools directory.
+//! * `ANDROID_NDK_HOME` or `ANDROID_NDK_ROOT`: path to the Android NDK directory.
+//!   * If neither is set, the latest NDK installed in the Android SDK's `ndk` directory is used.
 //! * `JAVA_HOME`: the Java SDK directory.
+//! * `ANDROID_JAVA_HOME`: the Java SDK directory to use for Android builds specifically.
+//!   * If set, this takes precedence over `JAVA_HOME`.
 //! * `JAVA_SOURCE_VERSION`: the Java version for source compatibility; 
 //!   equivalent to the `--source` javac option, e.g., `17` for Java 1.7.
 //! * `JAVA_TARGET_VERSION`: the Java version for target compatibility; 
 //!   equivalent to the `--target` javac option, e.g., `17` for Java 1.7.
+//!   * If both are set to the same version, [`JavaBuild::from_env()`] uses
+//!     `--release` instead, if supported by the JDK.
+//! * `CLASSPATH`: the default class path for `java` and `javac`.
+//!   * This is only explicitly incorporated into a [`JavaBuild`] or [`JavaRun`]
+//!     if `inherit_classpath_env(true)` is set.
+//! * `JAVA_OPTS`, `ANDROID_JAVAC_OPTS`: extra options for `java` and `javac`, respectively.
+//!   * These are only used if `inherit_java_opts(true)` is set
+//!     on a [`JavaRun`] or [`JavaBuild`], respectively.
+//! * `ANDROID_STRICT`: if set to `1` or `true`, never fall back to the latest installed
+//!   platform or build tools when no version is specified; lookups fail instead.
+//!
+//! Surrounding whitespace and quotes, as well as trailing path separators,
+//! are removed from the values of the above environment variables that specify paths.
+//!
+//! ## Optional features
+//! * `log`: reports diagnostics about toolchain discovery (e.g., which SDK, platform,
+//!   or JDK was chosen, and which fallback was used) via the [`log`](https://docs.rs/log) crate,
+//!   such that they can be viewed with a logger like `env_logger` and `RUST_LOG`.
+//!   Without this feature, only warnings are printed to stderr.
+//! * `cargo`: enables helpers that are only meaningful within a Cargo build script,
+//!   such as [`ResolvedPaths::emit_rustc_env()`], and makes [`android_jar()`] fall back to
+//!   the API level of the Android target being built (see [`cargo_target_api_level()`]).
+//!   It also prints a `cargo:rerun-if-env-changed` line for each of the above environment
+//!   variables that is consulted, such that the build script re-runs when one changes.
+//!   Note that this disables Cargo's default of re-running the build script
+//!   whenever any file in the package changes.
+//! * `serde`: implements `Serialize` and `Deserialize` for [`JavaBuild`], [`JavaRun`],
+//!   and [`DebugInfo`], such that build configurations can be loaded from a file.
+//!   All paths and arguments are represented as UTF-8 strings.
 //!
 //! ## Acknowledgments
 //! This crate simplifies some code found in other crates:
@@ -41,11 +88,124 @@
 //!   for arguments that can be passed into `java` and `javac` commands.
 //!
 
+/// Reports a warning about toolchain discovery.
+///
+/// If the `log` feature is enabled, this uses [`log::warn!`];
+/// otherwise, the message is printed to stderr.
+macro_rules! log_warn {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        ::log::warn!($($arg)*);
+        #[cfg(not(feature = "log"))]
+        eprintln!($($arg)*);
+    }};
+}
+
+/// Reports a decision made during toolchain discovery,
+/// e.g., which SDK or JDK was chosen and where it came from.
+///
+/// This is only emitted if the `log` feature is enabled.
+macro_rules! log_debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        ::log::debug!($($arg)*);
+        // Type-check (but don't evaluate) the arguments to avoid unused variable warnings.
+        #[cfg(not(feature = "log"))]
+        if false { let _ = format_args!($($arg)*); }
+    }};
+}
+
+/// Serializes tests that read or modify environment variables,
+/// which are shared by all tests running in parallel within the test process.
+#[cfg(test)]
+pub(crate) static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// A uniquely-named temporary directory for tests, which is removed when dropped.
+///
+/// The guard must be kept alive for as long as the directory is used,
+/// e.g., not dropped right after passing its path to a builder.
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct TestDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    /// Creates a new empty directory whose name includes the given `name`.
+    ///
+    /// A per-process counter ensures that each call yields a distinct path,
+    /// so that per-path caches (e.g., of `javac` versions) never see a stale directory.
+    pub(crate) fn new(name: &str) -> Self {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "android-build-{}-{}-{}",
+            std::process::id(),
+            name,
+            COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TestDir {
+    type Target = std::path::Path;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl AsRef<std::path::Path> for TestDir {
+    fn as_ref(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl AsRef<std::ffi::OsStr> for TestDir {
+    fn as_ref(&self) -> &std::ffi::OsStr {
+        self.0.as_os_str()
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
 
 mod java_build;
 mod java_run;
+mod java_disassemble;
+mod jar_build;
+mod java_deps;
+mod javadoc;
+mod dex_dump;
+mod android_apk;
+mod android_target;
+mod class_file;
+mod diagnostic;
 mod env_paths;
+mod files;
+mod process;
+mod resolved_command;
+#[cfg(feature = "serde")]
+mod serde_os;
 
 pub use java_build::*;
 pub use java_run::*;
+pub use java_disassemble::*;
+pub use jar_build::*;
+pub use java_deps::*;
+pub use javadoc::*;
+pub use dex_dump::*;
+pub use android_apk::*;
+pub use android_target::*;
+pub use class_file::class_file_major_version;
+pub use diagnostic::{Diagnostic, Severity, parse_javac_diagnostics};
+pub use files::collect_files_by_ext;
 pub use env_paths::*;
+pub use resolved_command::ResolvedCommand;
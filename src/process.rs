@@ -0,0 +1,276 @@
+//! Internal helpers for executing the commands built by this crate.
+
+use std::ffi::OsStr;
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::time::{Duration, Instant};
+
+/// How often to check whether a child process with a timeout has exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Executes the given command with inherited stdio and waits for it to finish.
+///
+/// If `stdin_data` is given, it is written to the child's stdin,
+/// which must have been configured as [`Stdio::piped()`](std::process::Stdio::piped).
+///
+/// If a `timeout` is given and the child process runs for longer than that,
+/// it is killed and an error of kind [`io::ErrorKind::TimedOut`] is returned.
+pub(crate) fn status_with_timeout(
+    cmd: &mut Command,
+    timeout: Option<Duration>,
+    stdin_data: Option<&[u8]>,
+) -> io::Result<ExitStatus> {
+    let Some(timeout) = timeout else {
+        return match stdin_data {
+            Some(_) => spawn_with_stdin(cmd, stdin_data)?.wait(),
+            None => cmd.status(),
+        };
+    };
+
+    let mut child = spawn_with_stdin(cmd, stdin_data)?;
+    wait_with_timeout(&mut child, cmd.get_program(), timeout)
+}
+
+/// Executes the given command, capturing its stdout and stderr, and waits for it to finish.
+///
+/// Stdin data and the `timeout` are handled in the same way as in [`status_with_timeout()`].
+pub(crate) fn output_with_timeout(
+    cmd: &mut Command,
+    timeout: Option<Duration>,
+    stdin_data: Option<&[u8]>,
+) -> io::Result<Output> {
+    let mut child = spawn_with_stdin(cmd.stdout(Stdio::piped()).stderr(Stdio::piped()), stdin_data)?;
+    let Some(timeout) = timeout else {
+        return child.wait_with_output();
+    };
+
+    // Read both pipes from separate threads, such that the child cannot block
+    // on a full pipe while we are waiting for it to exit.
+    fn read_all<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<Vec<u8>> {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    }
+    let stdout = read_all(child.stdout.take());
+    let stderr = read_all(child.stderr.take());
+    let status = wait_with_timeout(&mut child, cmd.get_program(), timeout)?;
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Waits for the given child process to exit, killing it if it runs for longer than `timeout`.
+fn wait_with_timeout(child: &mut Child, program: &OsStr, timeout: Duration) -> io::Result<ExitStatus> {
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if start.elapsed() >= timeout {
+            // The child may have exited in the meantime, so ignore errors here.
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("{:?} timed out after {:?}", program, timeout),
+            ));
+        }
+        std::thread::sleep(POLL_INTERVAL.min(timeout.saturating_sub(start.elapsed())));
+    }
+}
+
+/// Spawns the given command, writing `stdin_data` (if any) to the child's stdin.
+///
+/// The data is written from a separate thread, such that a child that fills up
+/// its stdout or stderr pipe before reading all of its stdin cannot deadlock.
+/// The child's stdin is closed once all data has been written.
+pub(crate) fn spawn_with_stdin(cmd: &mut Command, stdin_data: Option<&[u8]>) -> io::Result<Child> {
+    let mut child = cmd.spawn()?;
+    if let (Some(data), Some(mut stdin)) = (stdin_data, child.stdin.take()) {
+        let data = data.to_vec();
+        // The child may exit without reading all of its stdin, so ignore errors here.
+        std::thread::spawn(move || { let _ = stdin.write_all(&data); });
+    }
+    Ok(child)
+}
+
+/// Splits the given string into words using shell-like rules, e.g., for options
+/// given via an environment variable like `JAVA_OPTS`.
+///
+/// Words are separated by whitespace, and may be quoted with `'` or `"`
+/// to include whitespace. A backslash escapes a following double quote,
+/// as well as a single quote or whitespace character outside of quotes;
+/// any other backslash is kept as is, such that Windows paths remain intact.
+/// No other shell features, such as variable expansion, are supported.
+pub(crate) fn split_shell_words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => word.get_or_insert_with(String::new).push(c),
+            (_, '\\') if chars.peek().is_some_and(|next| {
+                *next == '"' || (quote.is_none() && (*next == '\'' || next.is_whitespace()))
+            }) => {
+                word.get_or_insert_with(String::new).extend(chars.next());
+            }
+            (Some('"'), '"') => quote = None,
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, _) if c.is_whitespace() => words.extend(word.take()),
+            _ => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// Returns the JVM system properties that make a Java tool encode its
+/// stdout and stderr using the given `charset`, e.g., `UTF-8`.
+///
+/// `stdout.encoding` and `stderr.encoding` are honored by JDK 19 and later,
+/// while older JDKs honor the `sun.`-prefixed properties.
+pub(crate) fn output_encoding_properties(charset: &str) -> [String; 4] {
+    [
+        format!("-Dstdout.encoding={charset}"),
+        format!("-Dstderr.encoding={charset}"),
+        format!("-Dsun.stdout.encoding={charset}"),
+        format!("-Dsun.stderr.encoding={charset}"),
+    ]
+}
+
+/// Returns an error describing the failure if the given `status` is not successful.
+///
+/// The error message includes the `tool_name`, the exit code,
+/// and the captured `stderr` output, if provided.
+pub(crate) fn check_status(tool_name: &str, status: ExitStatus, stderr: Option<&[u8]>) -> io::Result<()> {
+    if status.success() {
+        return Ok(());
+    }
+    let mut msg = match status.code() {
+        Some(code) => format!("`{}` failed with exit code {}", tool_name, code),
+        None => format!("`{}` was terminated by a signal", tool_name),
+    };
+    if let Some(stderr) = stderr.map(String::from_utf8_lossy) {
+        let stderr = stderr.trim();
+        if !stderr.is_empty() {
+            msg.push_str(":\n");
+            msg.push_str(stderr);
+        }
+    }
+    Err(io::Error::other(msg))
+}
+
+/// Prints the given command instead of executing it, returning a successful [`ExitStatus`].
+///
+/// If the `cargo` feature is enabled, the command is printed as a Cargo warning
+/// such that it is visible in the build output; otherwise, it is printed to stderr.
+pub(crate) fn dry_run(cmd: &Command) -> ExitStatus {
+    #[cfg(feature = "cargo")]
+    println!("cargo:warning=[dry run] {}", command_string(cmd));
+    #[cfg(not(feature = "cargo"))]
+    eprintln!("[dry run] {}", command_string(cmd));
+    ExitStatus::default()
+}
+
+/// Prints the given warning message about a build configuration.
+///
+/// If the `cargo` feature is enabled, the message is printed as a Cargo warning
+/// such that it is visible in the build output; otherwise, it is logged as a warning.
+pub(crate) fn build_warning(msg: &str) {
+    #[cfg(feature = "cargo")]
+    println!("cargo:warning={msg}");
+    #[cfg(not(feature = "cargo"))]
+    log_warn!("{msg}");
+}
+
+/// Returns a human-readable string of the given command's program and arguments,
+/// in which any program or argument containing whitespace or quotes is quoted.
+///
+/// This is intended for display purposes only, not for execution by a shell.
+pub(crate) fn command_string(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+                format!("{:?}", arg)
+            } else {
+                arg.into_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_shell_words() {
+        assert_eq!(split_shell_words("  -Xmx1g   -Dfoo=bar "), ["-Xmx1g", "-Dfoo=bar"]);
+        assert_eq!(
+            split_shell_words(r#"-Dname="two words" '-Dsingle=a b' -Dempty="""#),
+            ["-Dname=two words", "-Dsingle=a b", "-Dempty="],
+        );
+        assert_eq!(split_shell_words(r#"-Dq=\"x\" a\ b it\'s"#), [r#"-Dq="x""#, "a b", "it's"]);
+        assert_eq!(split_shell_words(r"-Dpath=C:\tools\jdk"), [r"-Dpath=C:\tools\jdk"]);
+        assert!(split_shell_words("   ").is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn kills_commands_that_time_out() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("5");
+        let start = Instant::now();
+        let err = status_with_timeout(&mut cmd, Some(Duration::from_millis(100)), None).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let status = status_with_timeout(&mut Command::new("true"), Some(Duration::from_secs(5)), None).unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn failed_status_includes_exit_code_and_stderr() {
+        use std::os::unix::process::ExitStatusExt;
+        assert!(check_status("d8", ExitStatus::from_raw(0), Some(b"ignored")).is_ok());
+        let err = check_status("d8", ExitStatus::from_raw(1 << 8), Some(b"  Error: missing class\n")).unwrap_err();
+        assert_eq!(err.to_string(), "`d8` failed with exit code 1:\nError: missing class");
+        let err = check_status("java", ExitStatus::from_raw(9), None).unwrap_err();
+        assert_eq!(err.to_string(), "`java` was terminated by a signal");
+    }
+
+    #[test]
+    fn quotes_command_string_args() {
+        let mut cmd = Command::new("javac");
+        cmd.args(["-d", "out dir", "", "A.java"]);
+        assert_eq!(command_string(&cmd), r#"javac -d "out dir" "" A.java"#);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn captures_output_with_stdin_data() {
+        for timeout in [None, Some(Duration::from_secs(5))] {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", "cat; echo oops >&2"]).stdin(Stdio::piped());
+            let output = output_with_timeout(&mut cmd, timeout, Some(b"hello\n")).unwrap();
+            assert!(output.status.success());
+            assert_eq!(output.stdout, b"hello\n");
+            assert_eq!(output.stderr, b"oops\n");
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! A fully-resolved command as plain data, for inspecting or passing around.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// A fully-resolved command, consisting of plain data rather than an opaque [`Command`].
+///
+/// Unlike a [`Command`], this can be inspected, compared, cloned,
+/// and (with the `serde` feature) serialized, e.g., to be handed off to a task runner.
+/// It can be obtained from a builder like [`JavaBuild::to_owned_command()`](crate::JavaBuild::to_owned_command)
+/// or from any [`Command`] via [`From`], and turned back into a [`Command`] via [`ResolvedCommand::to_command()`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ResolvedCommand {
+    /// The path to the program to be executed.
+    pub program: PathBuf,
+    /// The arguments to be passed to the program.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::vec"))]
+    pub args: Vec<OsString>,
+    /// The environment variables explicitly set for the program,
+    /// in addition to those inherited from the current process.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::pairs"))]
+    pub env: Vec<(OsString, OsString)>,
+    /// The working directory of the program, if not the current directory.
+    pub cwd: Option<PathBuf>,
+}
+
+impl ResolvedCommand {
+    /// Returns a new [`Command`] with the program, arguments, environment variables,
+    /// and working directory of this `ResolvedCommand`.
+    pub fn to_command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args)
+            .envs(self.env.iter().map(|(k, v)| (k, v)));
+        if let Some(cwd) = &self.cwd {
+            cmd.current_dir(cwd);
+        }
+        cmd
+    }
+}
+
+impl From<&Command> for ResolvedCommand {
+    /// Captures the program, arguments, explicitly-set environment variables,
+    /// and working directory of the given `Command`.
+    ///
+    /// Environment variables that were explicitly removed from the `Command` are omitted.
+    fn from(cmd: &Command) -> Self {
+        Self {
+            program: PathBuf::from(cmd.get_program()),
+            args: cmd.get_args().map(OsString::from).collect(),
+            env: cmd.get_envs()
+                .filter_map(|(k, v)| Some((k.to_os_string(), v?.to_os_string())))
+                .collect(),
+            cwd: cmd.get_current_dir().map(PathBuf::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_command() {
+        let mut cmd = Command::new("javac");
+        cmd.args(["-d", "out"]).env("PATH", "/jdk/bin").env_remove("CLASSPATH").current_dir("/src");
+        let resolved = ResolvedCommand::from(&cmd);
+        assert_eq!(resolved, ResolvedCommand {
+            program: "javac".into(),
+            args: vec!["-d".into(), "out".into()],
+            env: vec![("PATH".into(), "/jdk/bin".into())],
+            cwd: Some("/src".into()),
+        });
+        assert_eq!(ResolvedCommand::from(&resolved.to_command()), resolved);
+    }
+}
@@ -0,0 +1,228 @@
+//! Helpers for (de)serializing `OsString` fields as plain strings with `serde`.
+//!
+//! By default, `serde` (de)serializes an `OsString` as a platform-specific enum,
+//! which is not suitable for human-written configuration files.
+//! These helpers instead require `OsString`s to be valid UTF-8.
+
+use std::ffi::{OsStr, OsString};
+use serde::{ser::Error, Deserialize, Deserializer, Serializer};
+
+fn to_str<E: Error>(s: &OsStr) -> Result<&str, E> {
+    s.to_str().ok_or_else(|| E::custom(format!("{:?} is not valid UTF-8", s)))
+}
+
+/// For fields of type `Option<OsString>`.
+pub(crate) mod option {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(value: &Option<OsString>, ser: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(s) => ser.serialize_some(to_str::<S::Error>(s)?),
+            None => ser.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Option<OsString>, D::Error> {
+        Ok(Option::<String>::deserialize(de)?.map(OsString::from))
+    }
+}
+
+/// For fields of type `Vec<OsString>`.
+pub(crate) mod vec {
+    use super::*;
+    use serde::ser::SerializeSeq;
+
+    pub(crate) fn serialize<S: Serializer>(value: &[OsString], ser: S) -> Result<S::Ok, S::Error> {
+        let mut seq = ser.serialize_seq(Some(value.len()))?;
+        for s in value {
+            seq.serialize_element(to_str::<S::Error>(s)?)?;
+        }
+        seq.end()
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<OsString>, D::Error> {
+        Ok(Vec::<String>::deserialize(de)?.into_iter().map(OsString::from).collect())
+    }
+}
+
+/// For fields of type `Vec<(OsString, OsString)>`, e.g., environment variables.
+pub(crate) mod pairs {
+    use super::*;
+    use serde::ser::SerializeSeq;
+
+    pub(crate) fn serialize<S: Serializer>(value: &[(OsString, OsString)], ser: S) -> Result<S::Ok, S::Error> {
+        let mut seq = ser.serialize_seq(Some(value.len()))?;
+        for (k, v) in value {
+            seq.serialize_element(&(to_str::<S::Error>(k)?, to_str::<S::Error>(v)?))?;
+        }
+        seq.end()
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<(OsString, OsString)>, D::Error> {
+        Ok(Vec::<(String, String)>::deserialize(de)?
+            .into_iter()
+            .map(|(k, v)| (OsString::from(k), OsString::from(v)))
+            .collect())
+    }
+}
+
+/// A minimal in-memory serializer and deserializer for round-trip tests,
+/// as this crate does not depend on a concrete data format like JSON.
+#[cfg(test)]
+pub(crate) mod round_trip {
+    use serde::de::{self, value::{Error, MapDeserializer, SeqDeserializer}, DeserializeOwned, IntoDeserializer, Visitor};
+    use serde::ser::{self, Error as _, Impossible, Serialize};
+
+    /// Serializes the given `value` and deserializes a new value from the result.
+    pub(crate) fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+        T::deserialize(value.serialize(ValueSerializer).unwrap()).unwrap()
+    }
+
+    #[derive(Debug)]
+    enum Value {
+        Bool(bool),
+        U64(u64),
+        Char(char),
+        Str(String),
+        None,
+        Some(Box<Value>),
+        Seq(Vec<Value>),
+        Struct(Vec<(Value, Value)>),
+    }
+
+    struct ValueSerializer;
+
+    fn unsupported<T>(what: &str) -> Result<T, Error> {
+        Err(Error::custom(format!("serializing {} is not supported", what)))
+    }
+
+    impl ser::Serializer for ValueSerializer {
+        type Ok = Value;
+        type Error = Error;
+        type SerializeSeq = SeqSerializer;
+        type SerializeTuple = SeqSerializer;
+        type SerializeTupleStruct = Impossible<Value, Error>;
+        type SerializeTupleVariant = Impossible<Value, Error>;
+        type SerializeMap = Impossible<Value, Error>;
+        type SerializeStruct = StructSerializer;
+        type SerializeStructVariant = Impossible<Value, Error>;
+
+        fn serialize_bool(self, v: bool) -> Result<Value, Error> { Ok(Value::Bool(v)) }
+        fn serialize_i8(self, _: i8) -> Result<Value, Error> { unsupported("i8") }
+        fn serialize_i16(self, _: i16) -> Result<Value, Error> { unsupported("i16") }
+        fn serialize_i32(self, _: i32) -> Result<Value, Error> { unsupported("i32") }
+        fn serialize_i64(self, _: i64) -> Result<Value, Error> { unsupported("i64") }
+        fn serialize_u8(self, v: u8) -> Result<Value, Error> { Ok(Value::U64(v.into())) }
+        fn serialize_u16(self, v: u16) -> Result<Value, Error> { Ok(Value::U64(v.into())) }
+        fn serialize_u32(self, v: u32) -> Result<Value, Error> { Ok(Value::U64(v.into())) }
+        fn serialize_u64(self, v: u64) -> Result<Value, Error> { Ok(Value::U64(v)) }
+        fn serialize_f32(self, _: f32) -> Result<Value, Error> { unsupported("f32") }
+        fn serialize_f64(self, _: f64) -> Result<Value, Error> { unsupported("f64") }
+        fn serialize_char(self, v: char) -> Result<Value, Error> { Ok(Value::Char(v)) }
+        fn serialize_str(self, v: &str) -> Result<Value, Error> { Ok(Value::Str(v.into())) }
+        fn serialize_bytes(self, _: &[u8]) -> Result<Value, Error> { unsupported("bytes") }
+        fn serialize_none(self) -> Result<Value, Error> { Ok(Value::None) }
+        fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Value, Error> {
+            Ok(Value::Some(Box::new(value.serialize(self)?)))
+        }
+        fn serialize_unit(self) -> Result<Value, Error> { unsupported("unit") }
+        fn serialize_unit_struct(self, _: &'static str) -> Result<Value, Error> { unsupported("unit structs") }
+        fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<Value, Error> {
+            Ok(Value::Str(variant.into()))
+        }
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<Value, Error> {
+            value.serialize(self)
+        }
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> Result<Value, Error> {
+            unsupported("newtype variants")
+        }
+        fn serialize_seq(self, _: Option<usize>) -> Result<SeqSerializer, Error> { Ok(SeqSerializer(Vec::new())) }
+        fn serialize_tuple(self, _: usize) -> Result<SeqSerializer, Error> { Ok(SeqSerializer(Vec::new())) }
+        fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, Error> {
+            unsupported("tuple structs")
+        }
+        fn serialize_tuple_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeTupleVariant, Error> {
+            unsupported("tuple variants")
+        }
+        fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> { unsupported("maps") }
+        fn serialize_struct(self, _: &'static str, _: usize) -> Result<StructSerializer, Error> {
+            Ok(StructSerializer(Vec::new()))
+        }
+        fn serialize_struct_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeStructVariant, Error> {
+            unsupported("struct variants")
+        }
+    }
+
+    struct SeqSerializer(Vec<Value>);
+
+    impl ser::SerializeSeq for SeqSerializer {
+        type Ok = Value;
+        type Error = Error;
+        fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+            self.0.push(value.serialize(ValueSerializer)?);
+            Ok(())
+        }
+        fn end(self) -> Result<Value, Error> { Ok(Value::Seq(self.0)) }
+    }
+
+    impl ser::SerializeTuple for SeqSerializer {
+        type Ok = Value;
+        type Error = Error;
+        fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+            ser::SerializeSeq::serialize_element(self, value)
+        }
+        fn end(self) -> Result<Value, Error> { ser::SerializeSeq::end(self) }
+    }
+
+    struct StructSerializer(Vec<(Value, Value)>);
+
+    impl ser::SerializeStruct for StructSerializer {
+        type Ok = Value;
+        type Error = Error;
+        fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+            self.0.push((Value::Str(key.into()), value.serialize(ValueSerializer)?));
+            Ok(())
+        }
+        fn end(self) -> Result<Value, Error> { Ok(Value::Struct(self.0)) }
+    }
+
+    impl<'de> IntoDeserializer<'de, Error> for Value {
+        type Deserializer = Self;
+        fn into_deserializer(self) -> Self { self }
+    }
+
+    impl<'de> de::Deserializer<'de> for Value {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self {
+                Value::Bool(v) => visitor.visit_bool(v),
+                Value::U64(v) => visitor.visit_u64(v),
+                Value::Char(v) => visitor.visit_char(v),
+                Value::Str(v) => visitor.visit_string(v),
+                Value::None => visitor.visit_none(),
+                Value::Some(v) => visitor.visit_some(*v),
+                Value::Seq(v) => de::Deserializer::deserialize_any(SeqDeserializer::new(v.into_iter()), visitor),
+                Value::Struct(v) => de::Deserializer::deserialize_any(MapDeserializer::new(v.into_iter()), visitor),
+            }
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            match self {
+                Value::Str(v) => v.into_deserializer().deserialize_enum(name, variants, visitor),
+                other => Err(de::Error::custom(format!("expected a unit variant, found {:?}", other))),
+            }
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct identifier ignored_any
+        }
+    }
+}